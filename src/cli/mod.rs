@@ -0,0 +1,617 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::collections::HashMap;
+use std::io;
+use std::io::BufRead;
+use std::path::{Path,PathBuf};
+
+use girdle::Dictionary;
+use girdle::dictionary::{Error,Result};
+use girdle::dictionary::{Feedback,FilterStats,Punctuation,SortMode};
+use girdle::dictionary::{GuessTree,WordList};
+use girdle::dictionary::SetType;
+use girdle::dictionary::matrix_to_csv;
+use girdle::dictionary::read_frequencies;
+
+use crate::DEFAULT_LENGTH;
+use crate::load_word_list;
+
+const REPL_CANDIDATES: usize = 10;
+const SELF_TEST_LIMIT: usize = 20;
+
+pub const USAGE: &str = "\
+Usage: girdle [OPTIONS]
+
+Options:
+  -d, --dict PATH         word list to load instead of the system one
+  -a, --answers PATH      restrict matches to the answers in this list
+      --frequencies PATH  weigh candidate likelihoods by the counts in this
+                          list of 'word<TAB>count' lines
+  -l, --length N          word length (default 5)
+  -i, --include CHARS     characters that must appear in the word
+  -x, --exclude CHARS     characters that must not appear in the word
+  -p, --positions PATTERN known positions, e.g. 'c..e.' ('.' is unknown)
+      --json              print matches as JSON without starting the GUI
+      --oneline           print matches on one comma-separated line without
+                          starting the GUI
+      --limit N           print at most N matches
+      --heatmap           print letter counts per position as CSV
+      --repl              read 'guess FEEDBACK' lines from standard input
+      --tree PATH         walk a precomputed guess tree, building and caching
+                          it at PATH first if it does not exist
+      --self-test         solve every word in the answer list using the
+                          suggestion engine and report the guess counts
+  -v, --verbose           report how many words each constraint eliminated
+      --tui               use the terminal interface (requires the tui
+                          feature)
+  -V, --version           print version information and exit
+  -h, --help              print this help and exit
+
+Without --json, --oneline or --heatmap the GUI is started. If no display is
+available and constraints were given, matches are printed to standard output.";
+
+pub struct Args {
+    pub dict: Option<PathBuf>,
+    pub answers: Option<PathBuf>,
+    pub frequencies: Option<PathBuf>,
+    pub length: usize,
+    pub include: String,
+    pub exclude: String,
+    pub positions: Option<String>,
+    pub json: bool,
+    pub oneline: bool,
+    pub limit: Option<usize>,
+    pub heatmap: bool,
+    pub repl: bool,
+    pub tree: Option<PathBuf>,
+    pub self_test: bool,
+    pub verbose: bool,
+    #[cfg(feature = "tui")]
+    pub tui: bool,
+    pub version: bool,
+    pub help: bool
+}
+
+impl Args {
+    pub fn parse<I>(args: I) -> Result<Args>
+        where I: Iterator<Item = String>
+    {
+        let mut parsed = Args {
+            dict: None,
+            answers: None,
+            frequencies: None,
+            length: 0,
+            include: String::new(),
+            exclude: String::new(),
+            positions: None,
+            json: false,
+            oneline: false,
+            limit: None,
+            heatmap: false,
+            repl: false,
+            tree: None,
+            self_test: false,
+            verbose: false,
+            #[cfg(feature = "tui")]
+            tui: false,
+            version: false,
+            help: false
+        };
+
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-d" | "--dict" => {
+                    let value = option_value(&arg, args.next())?;
+                    parsed.dict = Some(PathBuf::from(value));
+                },
+
+                "-a" | "--answers" => {
+                    let value = option_value(&arg, args.next())?;
+                    parsed.answers = Some(PathBuf::from(value));
+                },
+
+                "--frequencies" => {
+                    let value = option_value(&arg, args.next())?;
+                    parsed.frequencies = Some(PathBuf::from(value));
+                },
+
+                "-l" | "--length" => {
+                    let value = option_value(&arg, args.next())?;
+                    parsed.length = match value.parse() {
+                        Ok(length) if length > 0 => length,
+                        _ => {
+                            let msg = format!("Invalid word length '{}'.",
+                                              value);
+                            return Err(Error::new(&msg));
+                        }
+                    };
+                },
+
+                "-i" | "--include" => {
+                    parsed.include = letters(&arg, args.next())?;
+                },
+
+                "-x" | "--exclude" => {
+                    parsed.exclude = letters(&arg, args.next())?;
+                },
+
+                "-p" | "--positions" => {
+                    let value = option_value(&arg, args.next())?
+                        .to_lowercase();
+                    if !value.chars().all(|ch| ch == '.' ||
+                                          ch.is_alphabetic())
+                    {
+                        let msg = format!("Invalid position pattern '{}'.",
+                                          value);
+                        return Err(Error::new(&msg));
+                    }
+                    parsed.positions = Some(value);
+                },
+
+                "--json" => parsed.json = true,
+                "--oneline" => parsed.oneline = true,
+                "--limit" => {
+                    let value = option_value(&arg, args.next())?;
+                    parsed.limit = match value.parse() {
+                        Ok(limit) => Some(limit),
+                        Err(_) => {
+                            let msg = format!("Invalid limit '{}'.", value);
+                            return Err(Error::new(&msg));
+                        }
+                    };
+                },
+                "--heatmap" => parsed.heatmap = true,
+                "--repl" => parsed.repl = true,
+                "--tree" => {
+                    let value = option_value(&arg, args.next())?;
+                    parsed.tree = Some(PathBuf::from(value));
+                },
+                "--self-test" => parsed.self_test = true,
+                "-v" | "--verbose" => parsed.verbose = true,
+                #[cfg(feature = "tui")]
+                "--tui" => parsed.tui = true,
+                "-V" | "--version" => parsed.version = true,
+                "-h" | "--help" => parsed.help = true,
+
+                _ => {
+                    let msg = format!("Unknown option '{}'.", arg);
+                    return Err(Error::new(&msg));
+                }
+            }
+        }
+
+        let pattern = parsed.positions.as_ref().map(|p| p.chars().count());
+        match (parsed.length, pattern) {
+            (0, Some(pattern)) => parsed.length = pattern,
+            (0, None) => parsed.length = DEFAULT_LENGTH,
+            (length, Some(pattern)) if length != pattern => {
+                let msg = format!("Position pattern must be {} characters.",
+                                  length);
+                return Err(Error::new(&msg));
+            },
+            _ => ()
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.json || self.oneline || self.heatmap || self.repl ||
+            self.self_test || self.tree.is_some()
+    }
+
+    pub fn has_constraints(&self) -> bool {
+        !self.include.is_empty() || !self.exclude.is_empty() ||
+            self.positions.is_some()
+    }
+
+    pub fn load_answers(&self) -> Result<Option<WordList>> {
+        match &self.answers {
+            Some(path) => {
+                let answers = WordList::load(&[path], Punctuation::Skip)?;
+                Ok(Some(answers))
+            },
+            None => Ok(None)
+        }
+    }
+
+    pub fn load_frequencies(&self) -> Result<Option<HashMap<String, u64>>> {
+        match &self.frequencies {
+            Some(path) => Ok(Some(read_frequencies(path)?)),
+            None => Ok(None)
+        }
+    }
+
+    pub fn apply(&self, dictionary: &Dictionary) {
+        for ch in self.exclude.chars() {
+            dictionary.add_char(SetType::Excluded, ch);
+        }
+
+        for ch in self.include.chars() {
+            dictionary.add_char(SetType::Included, ch);
+        }
+
+        if let Some(positions) = &self.positions {
+            for (i, ch) in positions.chars().enumerate() {
+                if ch != '.' {
+                    dictionary.set_char_position(i+1, ch);
+                }
+            }
+        }
+    }
+}
+
+pub fn version() -> String {
+    let mut features = Vec::new();
+    if cfg!(feature = "tui") { features.push("tui"); }
+    if cfg!(feature = "embedded-dict") { features.push("embedded-dict"); }
+    if cfg!(feature = "gzip") { features.push("gzip"); }
+    if cfg!(feature = "ocr") { features.push("ocr"); }
+    if cfg!(feature = "watch") { features.push("watch"); }
+
+    let features = match features.is_empty() {
+        true => String::from("none"),
+        false => features.join(", ")
+    };
+
+    format!("girdle {} ({} {} build, features: {})",
+            env!("CARGO_PKG_VERSION"), std::env::consts::OS,
+            if cfg!(debug_assertions) { "debug" } else { "release" },
+            features)
+}
+
+pub fn run(args: &Args) -> Result<()> {
+    let word_list = load_word_list(args.dict.as_deref())?;
+    let dictionary = Dictionary::from_word_list(&word_list, args.length);
+    if let Some(answers) = args.load_answers()? {
+        dictionary.set_answers(answers.words());
+    }
+    args.apply(&dictionary);
+
+    if args.self_test {
+        return self_test(&dictionary, args.verbose);
+    }
+
+    if args.repl {
+        return repl(&dictionary);
+    }
+
+    if let Some(path) = &args.tree {
+        return walk_tree(&dictionary, path);
+    }
+
+    if args.heatmap {
+        print!("{}", matrix_to_csv(&dictionary.position_letter_matrix()));
+        return Ok(());
+    }
+
+    let mut matches = match args.verbose {
+        true => {
+            let (matches, stats) = dictionary.matches_with_stats();
+            print_stats(&stats);
+            matches
+        },
+        false => dictionary.matches_answers_only()
+    };
+    if let Some(limit) = args.limit {
+        matches.truncate(limit);
+    }
+
+    if args.json {
+        println!("{}", to_json(args.length, &matches));
+    } else if args.oneline {
+        println!("{}", matches.join(","));
+    } else {
+        for word in &matches {
+            println!("{}", word);
+        }
+    }
+    Ok(())
+}
+
+fn repl(dictionary: &Dictionary) -> Result<()> {
+    dictionary.set_sort_mode(SortMode::ByScore);
+    print_candidates(dictionary);
+
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [] => continue,
+
+            ["reset"] => {
+                dictionary.reset();
+                print_candidates(dictionary);
+            },
+
+            [guess, feedback] => {
+                let result = Feedback::parse(feedback)
+                    .and_then(|feedback| {
+                        dictionary.apply_feedback(guess, &feedback)
+                    });
+
+                match result {
+                    Ok(()) => print_candidates(dictionary),
+                    Err(error) => eprintln!("girdle: {}", error)
+                }
+            },
+
+            _ => eprintln!("girdle: expected 'guess FEEDBACK' or 'reset'.")
+        }
+    }
+    Ok(())
+}
+
+fn self_test(dictionary: &Dictionary, verbose: bool) -> Result<()> {
+    if !dictionary.has_answers() {
+        return Err(Error::new("--self-test requires an answer list."));
+    }
+
+    dictionary.reset();
+    let mut answers = dictionary.matches_answers_only();
+    answers.sort();
+    if answers.is_empty() {
+        let msg = format!("No {}-letter answers to test.",
+                          dictionary.length());
+        return Err(Error::new(&msg));
+    }
+
+    let opening = dictionary.suggest_guess()
+        .ok_or_else(|| Error::new("No opening guess available."))?;
+    let mut second: HashMap<Feedback, Option<String>> = HashMap::new();
+
+    let mut total = 0;
+    let mut worst: Option<(usize, &str)> = None;
+    let mut failed = Vec::new();
+    for answer in &answers {
+        dictionary.reset();
+        let mut guesses = Vec::new();
+        let mut guess = Some(opening.clone());
+
+        while let Some(current) = guess.take() {
+            let feedback = Feedback::compute(&current, answer);
+            dictionary.apply_feedback(&current, &feedback)?;
+            guesses.push(current);
+            if feedback.is_solved() || guesses.len() >= SELF_TEST_LIMIT {
+                break;
+            }
+
+            guess = match guesses.len() {
+                1 => second.entry(feedback)
+                    .or_insert_with(|| dictionary.suggest_guess())
+                    .clone(),
+                _ => dictionary.suggest_guess()
+            };
+        }
+
+        if verbose {
+            eprintln!("girdle: {}: {}", answer, guesses.join(" "));
+        }
+
+        if guesses.last() != Some(answer) {
+            failed.push(answer.as_str());
+            continue;
+        }
+
+        total += guesses.len();
+        if worst.is_none_or(|(count, _)| guesses.len() > count) {
+            worst = Some((guesses.len(), answer));
+        }
+    }
+    dictionary.reset();
+
+    let solved = answers.len() - failed.len();
+    println!("opening guess: {}", opening);
+    println!("solved: {} of {}", solved, answers.len());
+    if let Some((count, answer)) = worst {
+        println!("average guesses: {:.3}", total as f64 / solved as f64);
+        println!("max guesses: {} ({})", count, answer);
+    }
+    if !failed.is_empty() {
+        println!("unsolved: {}", failed.join(" "));
+    }
+    Ok(())
+}
+
+fn walk_tree(dictionary: &Dictionary, path: &Path) -> Result<()> {
+    let tree = match path.exists() {
+        true => GuessTree::load(path)?,
+        false => {
+            eprintln!("girdle: building the guess tree, which may take a \
+                       while");
+            let tree = dictionary.build_decision_tree()?;
+            tree.save(path)?;
+            eprintln!("girdle: saved a guess tree of depth {} to {}",
+                      tree.depth(), path.display());
+            tree
+        }
+    };
+
+    if tree.guess().chars().count() != dictionary.length() {
+        let msg = format!("{} was not built for {}-letter words.",
+                          path.display(), dictionary.length());
+        return Err(Error::new(&msg));
+    }
+
+    let mut node = &tree;
+    println!("guess: {}", node.guess());
+
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let feedback = match Feedback::parse(line) {
+            Ok(feedback) => feedback,
+            Err(error) => {
+                eprintln!("girdle: {}", error);
+                continue;
+            }
+        };
+
+        if feedback.is_solved() {
+            println!("solved: {}", node.guess());
+            break;
+        }
+
+        match node.next(&feedback) {
+            Some(next) => {
+                node = next;
+                println!("guess: {}", node.guess());
+            },
+            None => eprintln!("girdle: no answer matches that feedback.")
+        }
+    }
+    Ok(())
+}
+
+fn print_candidates(dictionary: &Dictionary) {
+    let matches = dictionary.matches_answers_only();
+    let top = matches.iter().take(REPL_CANDIDATES)
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+
+    println!("{} remaining: {}", matches.len(), top.join(" "));
+}
+
+fn print_stats(stats: &FilterStats) {
+    let stages = [
+        ("excluded letters", stats.excluded),
+        ("misplaced letters", stats.misplaced),
+        ("intersection", stats.intersection),
+        ("included letters", stats.included),
+        ("known positions", stats.positions),
+        ("letter counts", stats.counts),
+        ("adjacent letters", stats.adjacent),
+        ("letter multiset", stats.multiset),
+        ("answer list", stats.answers)
+    ];
+
+    eprintln!("girdle: {} candidates", stats.candidates);
+    for (stage, eliminated) in stages {
+        eprintln!("girdle:   {:<18} -{}", stage, eliminated);
+    }
+    eprintln!("girdle: {} remaining", stats.remaining);
+}
+
+fn option_value(option: &str, value: Option<String>) -> Result<String> {
+    match value {
+        Some(value) => Ok(value),
+        None => {
+            let msg = format!("Option '{}' requires a value.", option);
+            Err(Error::new(&msg))
+        }
+    }
+}
+
+fn letters(option: &str, value: Option<String>) -> Result<String> {
+    let value = option_value(option, value)?.to_lowercase();
+    if !value.chars().all(|ch| ch.is_alphabetic()) {
+        let msg = format!("Option '{}' only accepts letters.", option);
+        return Err(Error::new(&msg));
+    }
+    Ok(value)
+}
+
+fn to_json(length: usize, matches: &[String]) -> String {
+    let words = matches.iter()
+        .map(|word| format!("\"{}\"", escape(word)))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!("{{\"length\":{},\"count\":{},\"matches\":[{}]}}",
+            length, matches.len(), words)
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::new();
+    for ch in s.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            ch if (ch as u32) < 0x20 => {
+                escaped.push_str(&format!("\\u{:04x}", ch as u32));
+            },
+            ch => escaped.push(ch)
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse(args) {
+            Ok(_) => panic!("{:?} should not parse", args),
+            Err(error) => error.to_string()
+        }
+    }
+
+    #[test]
+    fn defaults_without_arguments() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.length, DEFAULT_LENGTH);
+        assert_eq!(args.limit, None);
+        assert!(args.positions.is_none());
+        assert!(!args.is_headless());
+        assert!(!args.has_constraints());
+    }
+
+    #[test]
+    fn length_and_limit_must_be_numbers() {
+        assert_eq!(parse(&["-l", "6"]).unwrap().length, 6);
+        assert_eq!(error(&["--length", "five"]),
+                   "Invalid word length 'five'.");
+        assert_eq!(error(&["--length", "0"]), "Invalid word length '0'.");
+
+        assert_eq!(parse(&["--limit", "3"]).unwrap().limit, Some(3));
+        assert_eq!(error(&["--limit", "-1"]), "Invalid limit '-1'.");
+        assert_eq!(error(&["--limit"]), "Option '--limit' requires a value.");
+    }
+
+    #[test]
+    fn length_must_agree_with_positions() {
+        assert_eq!(error(&["--length", "4", "--positions", "c..e."]),
+                   "Position pattern must be 4 characters.");
+        assert_eq!(error(&["-p", "c..e.", "-l", "6"]),
+                   "Position pattern must be 6 characters.");
+        assert_eq!(parse(&["-l", "5", "-p", "c..e."]).unwrap().length, 5);
+    }
+
+    #[test]
+    fn positions_set_the_length() {
+        let args = parse(&["--positions", "C..E"]).unwrap();
+        assert_eq!(args.positions.as_deref(), Some("c..e"));
+        assert_eq!(args.length, 4);
+        assert!(args.has_constraints());
+
+        assert_eq!(error(&["-p", "c.-e."]),
+                   "Invalid position pattern 'c.-e.'.");
+    }
+
+    #[test]
+    fn letters_and_unknown_options() {
+        let args = parse(&["-i", "AE", "-x", "st", "--json"]).unwrap();
+        assert_eq!(args.include, "ae");
+        assert_eq!(args.exclude, "st");
+        assert!(args.is_headless());
+
+        assert_eq!(error(&["--include", "a1"]),
+                   "Option '--include' only accepts letters.");
+        assert_eq!(error(&["--bogus"]), "Unknown option '--bogus'.");
+    }
+}
@@ -0,0 +1,87 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::fs;
+use std::io;
+use std::fs::File;
+use std::io::{BufRead,BufReader,Write};
+use std::path::Path;
+
+use girdle::dictionary::Result;
+
+const DEFAULT_RESULTS_FONT: &str = "Monospace 11";
+const DEFAULT_RESULT_COLUMNS: usize = 1;
+
+pub struct Config {
+    pub results_font: String,
+    pub result_columns: usize,
+    pub keep_above: bool
+}
+
+impl Config {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config> {
+        let mut config = Config::default();
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(config);
+            },
+            Err(error) => return Err(error.into())
+        };
+
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                config.set(key.trim(), value.trim());
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = File::create(path)?;
+        writeln!(file, "results_font = {}", self.results_font)?;
+        writeln!(file, "result_columns = {}", self.result_columns)?;
+        writeln!(file, "keep_above = {}", self.keep_above)?;
+        Ok(())
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "results_font" => self.results_font = String::from(value),
+            "result_columns" => {
+                if let Ok(columns) = value.parse::<usize>() {
+                    self.result_columns = columns.max(1);
+                }
+            },
+            "keep_above" => {
+                if let Ok(keep_above) = value.parse::<bool>() {
+                    self.keep_above = keep_above;
+                }
+            },
+            _ => ()
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            results_font: String::from(DEFAULT_RESULTS_FONT),
+            result_columns: DEFAULT_RESULT_COLUMNS,
+            keep_above: false
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use crate::dictionary::Dictionary;
+use crate::dictionary::Error;
+use crate::dictionary::Feedback;
+use crate::dictionary::Result;
+use crate::dictionary::WordList;
+
+pub struct BoardSet
+{
+    length: usize,
+    boards: Vec<Dictionary>
+}
+
+impl BoardSet {
+    pub fn new(word_list: &WordList, length: usize, count: usize)
+        -> BoardSet
+    {
+        let boards = (0..count)
+            .map(|_| Dictionary::from_word_list(word_list, length))
+            .collect();
+
+        BoardSet { length, boards }
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn len(&self) -> usize {
+        self.boards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boards.is_empty()
+    }
+
+    pub fn board(&self, index: usize) -> &Dictionary {
+        &self.boards[index]
+    }
+
+    pub fn boards(&self) -> &[Dictionary] {
+        &self.boards
+    }
+
+    pub fn set_answers(&self, answers: &[String]) {
+        for board in &self.boards {
+            board.set_answers(answers);
+        }
+    }
+
+    pub fn is_solved(&self, index: usize) -> bool {
+        self.boards[index].history().last()
+            .is_some_and(|(_, feedback)| feedback.is_solved())
+    }
+
+    pub fn apply_guess(&self, guess: &str, feedback: &[Option<Feedback>])
+        -> Result<()>
+    {
+        if feedback.len() != self.boards.len() {
+            let msg = format!("Expected feedback for {} boards.",
+                              self.boards.len());
+            return Err(Error::new(&msg));
+        }
+
+        for (i, feedback) in feedback.iter().enumerate() {
+            if let Some(feedback) = feedback {
+                if feedback.len() != self.length {
+                    let msg = format!("Feedback for board {} must be {} \
+                                       characters.", i + 1, self.length);
+                    return Err(Error::new(&msg));
+                }
+            }
+        }
+
+        for (board, feedback) in self.boards.iter().zip(feedback) {
+            if let Some(feedback) = feedback {
+                board.apply_feedback(guess, feedback)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn reset(&self) {
+        for board in &self.boards {
+            board.reset();
+        }
+    }
+}
@@ -6,22 +6,158 @@ use std::fs;
 use std::io;
 
 use std::cell::{Ref,RefCell};
-use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead,BufReader};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap,HashMap,HashSet};
+use std::fs::{File,OpenOptions};
+use std::io::{BufRead,BufReader,Read,Write};
+use std::path::{Path,PathBuf};
+use std::rc::Rc;
 
 use crate::dictionary::Error;
+use crate::dictionary::Feedback;
+use crate::dictionary::GuessTree;
+use crate::dictionary::LetterState;
+use crate::dictionary::DictionaryState;
 use crate::dictionary::Result;
+use crate::dictionary::Warning;
+#[cfg(feature = "gzip")]
+use flate2::read::MultiGzDecoder;
+
+#[cfg(feature = "embedded-dict")]
+const EMBEDDED_WORDS: &str = include_str!("../../data/words.txt");
+#[cfg(feature = "embedded-dict")]
+const EMBEDDED_PATH: &str = "(embedded)";
+
+// Longer lines can't be words of any length worth solving, and refusing them
+// keeps a malformed file from being read into memory all at once.
+pub const MAX_LINE_LENGTH: usize = 256;
+
+const TIE_EPSILON: f64 = 1e-9;
+
+// Bytes per line in a typical word list, used to size buffers up front.
+const AVERAGE_LINE: usize = 9;
+
+// Words are only kept when the filter accepts them, and the filter is
+// applied again whenever the word list is reloaded.
+pub type WordFilter = Rc<dyn Fn(&str) -> bool>;
+
+pub struct WordList
+{
+    path: PathBuf,
+    embedded: bool,
+    punctuation: Punctuation,
+    blacklist: Option<PathBuf>,
+    filter: Option<WordFilter>,
+    max_line: usize,
+    words: Vec<String>
+}
 
 pub struct Dictionary
 {
-    words: Vec<String>,
+    length: usize,
+    source: Option<PathBuf>,
+    punctuation: Punctuation,
+    blacklist: Option<PathBuf>,
+    filter: Option<WordFilter>,
+    max_line: usize,
+    words: RefCell<Vec<String>>,
+    initial_count: RefCell<usize>,
+    lookup: RefCell<HashSet<String>>,
+    alphabet: RefCell<HashSet<char>>,
     include: RefCell<HashSet<char>>,
     exclude: RefCell<HashSet<char>>,
-    positions: RefCell<[char; 5]>,
+    positions: RefCell<Vec<char>>,
+    allowed: RefCell<Vec<HashSet<char>>>,
+    not_at: RefCell<HashMap<char, HashSet<usize>>>,
+    present: RefCell<HashMap<char, HashSet<usize>>>,
+    min_counts: RefCell<HashMap<char, usize>>,
+    parity: RefCell<HashMap<char, bool>>,
+    adjacent: RefCell<HashSet<(char, char)>>,
+    multiset: RefCell<Option<Vec<char>>>,
+    disabled: RefCell<HashSet<Constraint>>,
+    history: RefCell<Vec<(String, Feedback)>>,
+    tried: RefCell<HashSet<char>>,
+    keep_greens: RefCell<bool>,
+    hide_guessed: RefCell<bool>,
+    intersection: RefCell<Option<HashSet<String>>>,
+    answers: RefCell<Option<HashSet<String>>>,
+    frequencies: RefCell<Option<HashMap<String, u64>>>,
+    sort_mode: RefCell<SortMode>,
     matches: RefCell<Option<Vec<String>>>
 }
 
+#[derive(Clone,Copy,Default)]
+pub struct FilterStats
+{
+    pub candidates: usize,
+    pub excluded: usize,
+    pub misplaced: usize,
+    pub intersection: usize,
+    pub included: usize,
+    pub positions: usize,
+    pub counts: usize,
+    pub adjacent: usize,
+    pub multiset: usize,
+    pub answers: usize,
+    pub remaining: usize
+}
+
+#[derive(Clone,Debug)]
+pub struct GuessMetrics
+{
+    pub guess: String,
+    pub entropy: f64,
+    pub worst_case: usize,
+    pub expected_remaining: f64
+}
+
+#[derive(Clone,Debug)]
+pub struct GuessComparison
+{
+    pub first: GuessMetrics,
+    pub second: GuessMetrics
+}
+
+#[derive(Clone,Debug)]
+pub struct MatchInfo
+{
+    pub word: String,
+    pub likelihood: f64,
+    pub expected_guesses: f64
+}
+
+pub struct MatchStream
+{
+    words: Vec<String>,
+    position: usize,
+    batch: usize,
+    matches: Vec<String>,
+    finished: bool
+}
+
+#[derive(Clone,Copy)]
+enum FilterStage
+{
+    Excluded,
+    Misplaced,
+    Intersection,
+    Included,
+    Positions,
+    Counts,
+    Adjacent,
+    Multiset
+}
+
+#[derive(Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Constraint
+{
+    Excluded,
+    Included,
+    Positions,
+    Misplaced,
+    Counts
+}
+
 #[derive(Clone,Copy)]
 pub enum SetType
 {
@@ -29,38 +165,510 @@ pub enum SetType
     Included
 }
 
-fn find_dictionary<'a>(dictionaries: &'a [&str]) -> Result<&'a str> {
+// How suggestions with the same expected information are ordered.
+#[derive(Clone,Copy,PartialEq)]
+pub enum TieBreak
+{
+    Candidates,
+    Alphabetical
+}
+
+#[derive(Clone,Copy,PartialEq)]
+pub enum Punctuation
+{
+    Skip,
+    Strip
+}
+
+#[derive(Clone,Copy,PartialEq)]
+pub enum SortMode
+{
+    Dictionary,
+    Alphabetical,
+    ByFrequency,
+    ByScore
+}
+
+fn find_dictionary<P: AsRef<Path>>(dictionaries: &[P]) -> Result<&Path> {
     for path in dictionaries {
         if let Ok(_) = fs::metadata(path) {
-            return Ok(path);
+            return Ok(path.as_ref());
         }
     }
 
-    Err(Error::new("Unable to find a word database."))
+    let tried = dictionaries.iter()
+        .map(|path| path.as_ref().display().to_string())
+        .collect::<Vec<_>>();
+    let msg = format!("Unable to find a word database (tried: {}).",
+                      tried.join(", "));
+    Err(Error::new(&msg))
 }
 
-impl Dictionary {
-    pub fn new<'a>(dictionaries: &'a [&str]) -> Result<Dictionary>
+fn filter_length(words: &[String], length: usize) -> Vec<String> {
+    words.iter()
+        .filter(|word| word.chars().count() == length)
+        .cloned().collect()
+}
+
+impl FilterStats {
+    fn record(&mut self, stage: FilterStage) {
+        match stage {
+            FilterStage::Excluded     => self.excluded += 1,
+            FilterStage::Misplaced    => self.misplaced += 1,
+            FilterStage::Intersection => self.intersection += 1,
+            FilterStage::Included     => self.included += 1,
+            FilterStage::Positions    => self.positions += 1,
+            FilterStage::Counts       => self.counts += 1,
+            FilterStage::Adjacent     => self.adjacent += 1,
+            FilterStage::Multiset     => self.multiset += 1
+        }
+    }
+}
+
+impl MatchStream {
+    pub fn next_batch(&mut self, dictionary: &Dictionary)
+        -> Option<&[String]>
+    {
+        if self.position >= self.words.len() {
+            if !self.finished {
+                self.finished = true;
+                let mut matches = self.matches.clone();
+                dictionary.sort_matches(&mut matches);
+                *dictionary.matches.borrow_mut() = Some(matches);
+            }
+            return None;
+        }
+
+        let end = (self.position + self.batch).min(self.words.len());
+        let found = self.matches.len();
+        let batch = &self.words[self.position..end];
+        self.matches.extend(dictionary.filter_matches(batch, None));
+        self.position = end;
+        Some(&self.matches[found..])
+    }
+
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+impl WordList {
+    pub fn load<P: AsRef<Path>>(dictionaries: &[P], punctuation: Punctuation)
+        -> Result<WordList>
+    {
+        WordList::read(dictionaries, punctuation, None, None,
+                       MAX_LINE_LENGTH, None)
+    }
+
+    pub fn with_blacklist<P, B>(dictionaries: &[P], punctuation: Punctuation,
+                                blacklist: B) -> Result<WordList>
+        where P: AsRef<Path>, B: AsRef<Path>
+    {
+        WordList::read(dictionaries, punctuation, Some(blacklist.as_ref()),
+                       None, MAX_LINE_LENGTH, None)
+    }
+
+    pub fn matching<P, F>(dictionaries: &[P], punctuation: Punctuation,
+                          filter: F) -> Result<WordList>
+        where P: AsRef<Path>, F: Fn(&str) -> bool + 'static
+    {
+        let filter: WordFilter = Rc::new(filter);
+        WordList::read(dictionaries, punctuation, None, Some(filter),
+                       MAX_LINE_LENGTH, None)
+    }
+
+    pub fn with_max_line<P: AsRef<Path>>(dictionaries: &[P],
+                                         punctuation: Punctuation,
+                                         max_line: usize) -> Result<WordList>
+    {
+        WordList::read(dictionaries, punctuation, None, None, max_line, None)
+    }
+
+    pub fn load_with_warnings<P: AsRef<Path>>(dictionaries: &[P],
+                                              punctuation: Punctuation,
+                                              blacklist: Option<&Path>)
+        -> Result<(WordList, Vec<Warning>)>
+    {
+        let mut warnings = Vec::new();
+        let word_list = WordList::read(dictionaries, punctuation, blacklist,
+                                       None, MAX_LINE_LENGTH,
+                                       Some(&mut warnings))?;
+        Ok((word_list, warnings))
+    }
+
+    fn read<P: AsRef<Path>>(dictionaries: &[P], punctuation: Punctuation,
+                            blacklist: Option<&Path>,
+                            filter: Option<WordFilter>, max_line: usize,
+                            warnings: Option<&mut Vec<Warning>>)
+        -> Result<WordList>
     {
         let database = find_dictionary(dictionaries)?;
-        let dictionary = Dictionary {
-            words: read_words(database)?,
+        let excluded = read_blacklist(blacklist)?;
+        let words = read_words(database, punctuation, &excluded,
+                               filter.as_ref(), max_line, warnings)
+            .map_err(|error| {
+                let msg = format!("Unable to read {}: {}.",
+                                  database.display(), error);
+                Error::new(&msg)
+            })?;
+        let word_list = WordList {
+            path: database.to_path_buf(),
+            embedded: false,
+            punctuation,
+            blacklist: blacklist.map(Path::to_path_buf),
+            filter,
+            max_line,
+            words
+        };
+        Ok(word_list)
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    pub fn embedded(punctuation: Punctuation, blacklist: Option<&Path>)
+        -> Result<WordList>
+    {
+        WordList::read_embedded(punctuation, blacklist, MAX_LINE_LENGTH, None)
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    pub fn embedded_with_warnings(punctuation: Punctuation,
+                                  blacklist: Option<&Path>)
+        -> Result<(WordList, Vec<Warning>)>
+    {
+        let mut warnings = Vec::new();
+        let word_list = WordList::read_embedded(punctuation, blacklist,
+                                                MAX_LINE_LENGTH,
+                                                Some(&mut warnings))?;
+        Ok((word_list, warnings))
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    fn read_embedded(punctuation: Punctuation, blacklist: Option<&Path>,
+                     max_line: usize, warnings: Option<&mut Vec<Warning>>)
+        -> Result<WordList>
+    {
+        let excluded = read_blacklist(blacklist)?;
+        let words = parse_words(EMBEDDED_WORDS.as_bytes(),
+                                EMBEDDED_WORDS.len(), punctuation,
+                                &excluded, None, max_line, warnings)?;
+        let word_list = WordList {
+            path: PathBuf::from(EMBEDDED_PATH),
+            embedded: true,
+            punctuation,
+            blacklist: blacklist.map(Path::to_path_buf),
+            filter: None,
+            max_line,
+            words
+        };
+        Ok(word_list)
+    }
+
+    pub fn reload(&self) -> Result<WordList> {
+        #[cfg(feature = "embedded-dict")]
+        if self.embedded {
+            return WordList::read_embedded(self.punctuation,
+                                           self.blacklist.as_deref(),
+                                           self.max_line, None);
+        }
+
+        WordList::read(&[&self.path], self.punctuation,
+                       self.blacklist.as_deref(), self.filter.clone(),
+                       self.max_line, None)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn is_embedded(&self) -> bool {
+        self.embedded
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn skipped_lengths(&self, lengths: &[usize]) -> usize {
+        self.words.iter()
+            .filter(|word| !lengths.contains(&word.chars().count()))
+            .count()
+    }
+}
+
+impl Dictionary {
+    pub fn new<P: AsRef<Path>>(dictionaries: &[P], length: usize)
+        -> Result<Dictionary>
+    {
+        let word_list = WordList::load(dictionaries, Punctuation::Skip)?;
+        Ok(Dictionary::from_word_list(&word_list, length))
+    }
+
+    pub fn with_blacklist<P, B>(dictionaries: &[P], length: usize,
+                                blacklist: B) -> Result<Dictionary>
+        where P: AsRef<Path>, B: AsRef<Path>
+    {
+        let word_list = WordList::with_blacklist(dictionaries,
+                                                 Punctuation::Skip,
+                                                 blacklist)?;
+        Ok(Dictionary::from_word_list(&word_list, length))
+    }
+
+    pub fn new_matching<P, F>(dictionaries: &[P], length: usize, filter: F)
+        -> Result<Dictionary>
+        where P: AsRef<Path>, F: Fn(&str) -> bool + 'static
+    {
+        let word_list = WordList::matching(dictionaries, Punctuation::Skip,
+                                           filter)?;
+        Ok(Dictionary::from_word_list(&word_list, length))
+    }
+
+    pub fn with_warnings<P: AsRef<Path>>(dictionaries: &[P], length: usize)
+        -> Result<(Dictionary, Vec<Warning>)>
+    {
+        let (word_list, warnings) =
+            WordList::load_with_warnings(dictionaries, Punctuation::Skip,
+                                         None)?;
+        Ok((Dictionary::from_word_list(&word_list, length), warnings))
+    }
+
+    pub fn from_words(words: &[String], length: usize) -> Dictionary
+    {
+        let words = filter_length(words, length);
+        Dictionary {
+            length,
+            source: None,
+            punctuation: Punctuation::Skip,
+            blacklist: None,
+            filter: None,
+            max_line: MAX_LINE_LENGTH,
+            initial_count: RefCell::new(words.len()),
+            lookup: RefCell::new(words.iter().cloned().collect()),
+            alphabet: RefCell::new(collect_alphabet(&words)),
+            words: RefCell::new(words),
             include: RefCell::new(HashSet::new()),
             exclude: RefCell::new(HashSet::new()),
-            positions: RefCell::new(['.'; 5]),
+            positions: RefCell::new(vec!['.'; length]),
+            allowed: RefCell::new(vec![HashSet::new(); length]),
+            not_at: RefCell::new(HashMap::new()),
+            present: RefCell::new(HashMap::new()),
+            min_counts: RefCell::new(HashMap::new()),
+            parity: RefCell::new(HashMap::new()),
+            adjacent: RefCell::new(HashSet::new()),
+            multiset: RefCell::new(None),
+            disabled: RefCell::new(HashSet::new()),
+            history: RefCell::new(Vec::new()),
+            tried: RefCell::new(HashSet::new()),
+            keep_greens: RefCell::new(false),
+            hide_guessed: RefCell::new(false),
+            intersection: RefCell::new(None),
+            answers: RefCell::new(None),
+            frequencies: RefCell::new(None),
+            sort_mode: RefCell::new(SortMode::Dictionary),
             matches: RefCell::new(None)
+        }
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    pub fn from_embedded(length: usize) -> Result<Dictionary>
+    {
+        let word_list = WordList::embedded(Punctuation::Skip, None)?;
+        Ok(Dictionary::from_word_list(&word_list, length))
+    }
+
+    pub fn from_word_list(word_list: &WordList, length: usize) -> Dictionary
+    {
+        let mut dictionary = Dictionary::from_words(&word_list.words, length);
+        if !word_list.embedded {
+            dictionary.source = Some(word_list.path.clone());
+        }
+        dictionary.punctuation = word_list.punctuation;
+        dictionary.blacklist = word_list.blacklist.clone();
+        dictionary.filter = word_list.filter.clone();
+        dictionary.max_line = word_list.max_line;
+        dictionary
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.words.borrow().len()
+    }
+
+    pub fn initial_count(&self) -> usize {
+        *self.initial_count.borrow()
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.remaining().len()
+    }
+
+    // Counts what `matches` would return without cloning the words or
+    // updating the cache.
+    pub fn remaining_count(&self) -> usize {
+        let history = self.history.borrow();
+        let hidden = |s: &str| {
+            self.hide_guessed() && history.iter().any(|(guess, _)| guess == s)
+        };
+
+        let count = |words: &[String]| {
+            words.iter()
+                .filter(|s| !hidden(s) && self.rejected_by(s).is_none())
+                .count()
+        };
+        match &*self.matches.borrow() {
+            Some(matches) => count(matches),
+            None => count(&self.words.borrow())
+        }
+    }
+
+    pub fn information_gained(&self) -> Option<f64> {
+        let initial = self.initial_count();
+        let current = self.match_count();
+        if initial == 0 || current == 0 {
+            return None;
+        }
+        Some((initial as f64 / current as f64).log2())
+    }
+
+    pub fn contains_word(&self, word: &str) -> bool {
+        self.lookup.borrow().contains(&word.to_lowercase())
+    }
+
+    pub fn alphabet(&self) -> Vec<char> {
+        let alphabet = self.alphabet.borrow();
+        let mut vec = alphabet.iter().copied().collect::<Vec<_>>();
+        vec.sort();
+        vec
+    }
+
+    pub fn set_alphabet(&self, alphabet: &str) {
+        *self.alphabet.borrow_mut() = alphabet.to_lowercase().chars()
+            .filter(|ch| ch.is_alphabetic())
+            .collect();
+    }
+
+    pub fn is_letter(&self, ch: char) -> bool {
+        let alphabet = self.alphabet.borrow();
+        ch.to_lowercase().all(|ch| alphabet.contains(&ch))
+    }
+
+    pub fn set_answers(&self, answers: &[String]) {
+        let answers = filter_length(answers, self.length);
+        *self.answers.borrow_mut() = Some(answers.into_iter().collect());
+    }
+
+    pub fn has_answers(&self) -> bool {
+        self.answers.borrow().is_some()
+    }
+
+    pub fn set_frequencies(&self, frequencies: &HashMap<String, u64>) {
+        let frequencies = frequencies.iter()
+            .map(|(word, count)| (word.to_lowercase(), *count))
+            .filter(|(word, _)| word.chars().count() == self.length)
+            .collect();
+        *self.frequencies.borrow_mut() = Some(frequencies);
+    }
+
+    pub fn has_frequencies(&self) -> bool {
+        self.frequencies.borrow().is_some()
+    }
+
+    pub fn reload(&self) -> Result<()> {
+        let source = match &self.source {
+            Some(source) => source,
+            None => return Err(Error::new("Dictionary has no source file."))
+        };
+
+        let blacklist = read_blacklist(self.blacklist.as_deref())?;
+        let words = match read_words(source, self.punctuation, &blacklist,
+                                     self.filter.as_ref(), self.max_line,
+                                     None)
+        {
+            Ok(words) => words,
+            Err(error) => {
+                let msg = format!("Unable to read {}: {}.", source.display(),
+                                  error);
+                return Err(Error::new(&msg));
+            }
+        };
+        let words = filter_length(&words, self.length);
+        *self.initial_count.borrow_mut() = words.len();
+        *self.lookup.borrow_mut() = words.iter().cloned().collect();
+        *self.alphabet.borrow_mut() = collect_alphabet(&words);
+        *self.words.borrow_mut() = words;
+        *self.matches.borrow_mut() = None;
+        Ok(())
+    }
+
+    pub fn blacklist_word(&self, word: &str) -> Result<()> {
+        let path = match &self.blacklist {
+            Some(path) => path,
+            None => return Err(Error::new("No blacklist file configured."))
         };
-        Ok(dictionary)
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let word = word.to_lowercase();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{}", word)?;
+
+        let mut words = self.words.borrow_mut();
+        words.retain(|w| *w != word);
+        *self.initial_count.borrow_mut() = words.len();
+        *self.alphabet.borrow_mut() = collect_alphabet(&words);
+        (*self.lookup.borrow_mut()).remove(&word);
+        if let Some(matches) = &mut *self.matches.borrow_mut() {
+            matches.retain(|w| *w != word);
+        }
+        Ok(())
     }
 
     pub fn reset(&self) {
+        self.reset_keep_history();
+        (*self.history.borrow_mut()).clear();
+    }
+
+    pub fn reset_keep_history(&self) {
         (*self.include.borrow_mut()).clear();
         (*self.exclude.borrow_mut()).clear();
-        *self.positions.borrow_mut() = ['.'; 5];
+        *self.positions.borrow_mut() = vec!['.'; self.length];
+        *self.allowed.borrow_mut() = vec![HashSet::new(); self.length];
+        (*self.not_at.borrow_mut()).clear();
+        (*self.present.borrow_mut()).clear();
+        (*self.min_counts.borrow_mut()).clear();
+        (*self.parity.borrow_mut()).clear();
+        (*self.adjacent.borrow_mut()).clear();
+        *self.multiset.borrow_mut() = None;
+        (*self.tried.borrow_mut()).clear();
+        *self.intersection.borrow_mut() = None;
         *self.matches.borrow_mut() = None;
     }
 
     pub fn add_char(&self, set_type: SetType, ch: char) {
+        (*self.tried.borrow_mut()).insert(ch);
         match set_type {
             SetType::Excluded => self.exclude_char(ch),
             SetType::Included => self.include_char(ch)
@@ -81,6 +689,87 @@ impl Dictionary {
         }
     }
 
+    pub fn min_count(&self, ch: char) -> usize {
+        *self.min_counts.borrow().get(&ch).unwrap_or(&0)
+    }
+
+    pub fn set_min_count(&self, ch: char, count: usize) {
+        if count < self.min_count(ch) {
+            *self.matches.borrow_mut() = None;
+        }
+
+        let mut min_counts = self.min_counts.borrow_mut();
+        match count {
+            0 => min_counts.remove(&ch),
+            count => min_counts.insert(ch, count)
+        };
+    }
+
+    pub fn clear_min_counts(&self) {
+        (*self.min_counts.borrow_mut()).clear();
+        *self.matches.borrow_mut() = None;
+    }
+
+    pub fn letter_parity(&self, ch: char) -> Option<bool> {
+        self.parity.borrow().get(&ch).copied()
+    }
+
+    pub fn set_letter_parity(&self, ch: char, even: bool) {
+        let ch = ch.to_lowercase().next().unwrap_or(ch);
+        let previous = self.parity.borrow_mut().insert(ch, even);
+        if previous.is_some_and(|previous| previous != even) {
+            *self.matches.borrow_mut() = None;
+        }
+    }
+
+    pub fn clear_letter_parity(&self, ch: char) {
+        let ch = ch.to_lowercase().next().unwrap_or(ch);
+        if self.parity.borrow_mut().remove(&ch).is_some() {
+            *self.matches.borrow_mut() = None;
+        }
+    }
+
+    // Pairs are stored in sorted order so that "ab" and "ba" are the same
+    // restriction.
+    pub fn forbid_adjacent(&self, a: char, b: char) {
+        let a = a.to_lowercase().next().unwrap_or(a);
+        let b = b.to_lowercase().next().unwrap_or(b);
+        (*self.adjacent.borrow_mut()).insert(adjacent_pair(a, b));
+    }
+
+    pub fn forbidden_adjacent(&self) -> Vec<(char, char)> {
+        let mut pairs = self.adjacent.borrow().iter()
+            .copied().collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    }
+
+    pub fn clear_adjacent(&self) {
+        (*self.adjacent.borrow_mut()).clear();
+        *self.matches.borrow_mut() = None;
+    }
+
+    // Only anagrams of `letters` match, using each letter exactly as many
+    // times as it appears in `letters`.
+    pub fn set_letter_multiset(&self, letters: &str) {
+        let mut letters = letters.to_lowercase().chars().collect::<Vec<_>>();
+        letters.sort();
+        *self.multiset.borrow_mut() = match letters.is_empty() {
+            true => None,
+            false => Some(letters)
+        };
+        *self.matches.borrow_mut() = None;
+    }
+
+    pub fn letter_multiset(&self) -> Option<String> {
+        self.multiset.borrow().as_ref()
+            .map(|letters| letters.iter().collect())
+    }
+
+    pub fn clear_letter_multiset(&self) {
+        self.set_letter_multiset("");
+    }
+
     pub fn excluded_chars(&self) -> Vec<char> {
         let exclude = self.exclude.borrow();
         let mut vec = exclude.iter()
@@ -97,125 +786,2678 @@ impl Dictionary {
         vec
     }
 
+    pub fn tried_chars(&self) -> Vec<char> {
+        let tried = self.tried.borrow();
+        let mut vec = tried.iter()
+            .copied().collect::<Vec<_>>();
+        vec.sort();
+        vec
+    }
+
+    pub fn untried_letters(&self) -> Vec<char> {
+        let tried = self.tried.borrow();
+        self.alphabet().into_iter()
+            .filter(|ch| !tried.contains(ch))
+            .collect()
+    }
+
+    // Letters no remaining candidate uses, apart from those already
+    // excluded. With no candidates left nothing can be learned from this.
+    pub fn impossible_letters(&self) -> HashSet<char> {
+        let matches = self.remaining();
+        if matches.is_empty() {
+            return HashSet::new();
+        }
+
+        let used = matches.iter()
+            .flat_map(|word| word.chars())
+            .collect::<HashSet<_>>();
+        let exclude = self.exclude.borrow();
+        self.alphabet.borrow().iter()
+            .filter(|ch| !used.contains(ch) && !exclude.contains(ch))
+            .copied()
+            .collect()
+    }
+
     pub fn set_char_position(&self, pos: usize, ch: char) {
-        if pos < 1 || pos > 5 {
-            panic!("`pos` must be between 1 and 5.")
+        if pos < 1 || pos > self.length {
+            panic!("`pos` must be between 1 and {}.", self.length)
         }
 
-        if ch == '.' {
-            *self.matches.borrow_mut() = None;
+        if ch != '.' {
+            (*self.tried.borrow_mut()).insert(ch);
         }
 
+        let previous = (*self.positions.borrow())[pos-1];
+        let mut relaxed = previous != '.' && previous != ch;
         (*self.include.borrow_mut()).remove(&ch);
-        (*self.exclude.borrow_mut()).remove(&ch);
+        if !self.keep_greens() {
+            relaxed |= (*self.exclude.borrow_mut()).remove(&ch);
+        } else if self.exclude.borrow().contains(&ch) {
+            // An excluded green is then only excluded elsewhere.
+            relaxed = true;
+        }
         (*self.positions.borrow_mut())[pos-1] = ch;
+        if relaxed {
+            *self.matches.borrow_mut() = None;
+        }
+    }
+
+    pub fn char_position(&self, pos: usize) -> Option<char> {
+        if pos < 1 || pos > self.length {
+            panic!("`pos` must be between 1 and {}.", self.length)
+        }
+
+        match (*self.positions.borrow())[pos-1] {
+            '.' => None,
+            ch => Some(ch)
+        }
+    }
+
+    pub fn try_set_char_position(&self, pos: usize, ch: char) -> Result<()> {
+        self.check_green(pos, ch)?;
+        self.set_char_position(pos, ch);
+        Ok(())
     }
 
     pub fn unset_char_position(&self, pos: usize) {
         self.set_char_position(pos, '.');
     }
 
-    pub fn matches(&self) -> Ref<Option<Vec<String>>> {
-        let mut matches = self.matches.borrow_mut();
-        *matches = match &*matches {
-            Some(matches) => Some(self.filter_matches(&matches)),
-            None          => Some(self.filter_matches(&self.words)),
-        };
-        drop(matches);
-
-        self.matches.borrow()
+    pub fn clear_positions(&self) {
+        *self.positions.borrow_mut() = vec!['.'; self.length];
+        *self.allowed.borrow_mut() = vec![HashSet::new(); self.length];
+        *self.matches.borrow_mut() = None;
     }
 
-    fn filter_matches(&self, matches: &Vec<String>) -> Vec<String> {
-        let matches: Vec<String> = matches.into_iter().filter(|s| {
-            if self.match_excluded(&s) {
-                return false;
-            }
+    // A position with allowed letters matches any one of them. An empty set
+    // leaves the position unrestricted.
+    pub fn set_allowed_at(&self, pos: usize, letters: &[char]) {
+        if pos < 1 || pos > self.length {
+            panic!("`pos` must be between 1 and {}.", self.length)
+        }
 
-            if self.match_included(&s) &&
-                self.match_positions(&s)
-            {
-                return true;
-            }
+        let letters: HashSet<char> = letters.iter()
+            .map(|ch| ch.to_lowercase().next().unwrap_or(*ch))
+            .collect();
+        (*self.tried.borrow_mut()).extend(letters.iter());
+        (*self.allowed.borrow_mut())[pos-1] = letters;
+        *self.matches.borrow_mut() = None;
+    }
 
-            return false;
-        }).map(|s| String::from(s)).collect();
+    pub fn allowed_at(&self, pos: usize) -> Vec<char> {
+        if pos < 1 || pos > self.length {
+            panic!("`pos` must be between 1 and {}.", self.length)
+        }
 
-        matches
+        let mut letters = self.allowed.borrow()[pos-1].iter()
+            .copied().collect::<Vec<_>>();
+        letters.sort();
+        letters
     }
 
-    fn exclude_char(&self, ch: char) {
-        (*self.include.borrow_mut()).remove(&ch);
-        (*self.exclude.borrow_mut()).insert(ch);
+    pub fn clear_allowed_at(&self, pos: usize) {
+        self.set_allowed_at(pos, &[]);
     }
 
-    fn include_char(&self, ch: char) {
-        (*self.exclude.borrow_mut()).remove(&ch);
-        (*self.include.borrow_mut()).insert(ch);
+    pub fn exclude_letter_at(&self, ch: char, positions: &[usize]) {
+        for pos in positions {
+            if *pos < 1 || *pos > self.length {
+                panic!("`pos` must be between 1 and {}.", self.length)
+            }
+        }
+
+        let ch = ch.to_lowercase().next().unwrap_or(ch);
+        (*self.tried.borrow_mut()).insert(ch);
+        for pos in positions {
+            self.forbid_char_at(ch, pos-1);
+        }
     }
 
-    fn remove_excluded_char(&self, ch: char) {
-        (*self.exclude.borrow_mut()).remove(&ch);
+    pub fn exclude_at_position(&self, pos: usize, ch: char) {
+        self.exclude_letter_at(ch, &[pos]);
+    }
+
+    pub fn allow_at_position(&self, pos: usize, ch: char) {
+        if pos < 1 || pos > self.length {
+            panic!("`pos` must be between 1 and {}.", self.length)
+        }
+
+        let ch = ch.to_lowercase().next().unwrap_or(ch);
+        let mut not_at = self.not_at.borrow_mut();
+        if let Some(indices) = not_at.get_mut(&ch) {
+            indices.remove(&(pos-1));
+            if indices.is_empty() {
+                not_at.remove(&ch);
+            }
+            *self.matches.borrow_mut() = None;
+        }
+    }
+
+    // A yellow letter is in the word but not at `pos`. Yellows are kept
+    // apart from the included letters and other forbidden positions, so
+    // removing one leaves everything else the user entered alone.
+    pub fn add_present_char(&self, ch: char, pos: usize) {
+        if pos < 1 || pos > self.length {
+            panic!("`pos` must be between 1 and {}.", self.length)
+        }
+
+        let ch = ch.to_lowercase().next().unwrap_or(ch);
+        (*self.tried.borrow_mut()).insert(ch);
+        if (*self.exclude.borrow_mut()).remove(&ch) {
+            *self.matches.borrow_mut() = None;
+        }
+        (*self.present.borrow_mut()).entry(ch)
+            .or_default()
+            .insert(pos-1);
+    }
+
+    pub fn remove_present_char(&self, ch: char, pos: usize) {
+        if pos < 1 || pos > self.length {
+            panic!("`pos` must be between 1 and {}.", self.length)
+        }
+
+        let ch = ch.to_lowercase().next().unwrap_or(ch);
+        let mut present = self.present.borrow_mut();
+        if let Some(indices) = present.get_mut(&ch) {
+            indices.remove(&(pos-1));
+            if indices.is_empty() {
+                present.remove(&ch);
+            }
+            *self.matches.borrow_mut() = None;
+        }
+    }
+
+    pub fn present_positions(&self, ch: char) -> Vec<usize> {
+        let ch = ch.to_lowercase().next().unwrap_or(ch);
+        let mut positions = self.present.borrow().get(&ch)
+            .map(|indices| indices.iter().map(|i| i + 1).collect::<Vec<_>>())
+            .unwrap_or_default();
+        positions.sort();
+        positions
+    }
+
+    pub fn apply_feedback(&self, guess: &str, feedback: &Feedback)
+        -> Result<()>
+    {
+        let guess = guess.to_lowercase();
+        let chars: Vec<char> = guess.chars().collect();
+        if chars.len() != self.length || feedback.len() != self.length {
+            let msg = format!("Guess and feedback must be {} characters.",
+                              self.length);
+            return Err(Error::new(&msg));
+        }
+
+        let states = feedback.states();
+        for (i, (ch, state)) in chars.iter().zip(states).enumerate() {
+            if *state == LetterState::Correct {
+                self.check_green(i+1, *ch)?;
+            }
+        }
+
+        for (i, (ch, state)) in chars.iter().zip(states).enumerate() {
+            (*self.tried.borrow_mut()).insert(*ch);
+            match state {
+                LetterState::Correct => self.set_char_position(i+1, *ch),
+
+                LetterState::Present => {
+                    self.include_char(*ch);
+                    self.forbid_char_at(*ch, i);
+                },
+
+                LetterState::Absent => {
+                    let found = chars.iter().zip(states).any(|(c, s)| {
+                        c == ch && *s != LetterState::Absent
+                    });
+
+                    if found || self.is_known_char(*ch) {
+                        self.forbid_char_at(*ch, i);
+                    } else {
+                        self.exclude_char(*ch);
+                    }
+                }
+            }
+        }
+
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for (ch, state) in chars.iter().zip(states) {
+            if *state != LetterState::Absent {
+                *counts.entry(*ch).or_insert(0) += 1;
+            }
+        }
+
+        for (ch, count) in counts {
+            if count > 1 && count > self.min_count(ch) {
+                self.set_min_count(ch, count);
+            }
+        }
+
+        (*self.history.borrow_mut()).push((guess, feedback.clone()));
+        Ok(())
+    }
+
+    // A single cell can't tell a repeated gray letter from a missing one, so
+    // a gray is only kept to its position once the letter is known to be in
+    // the word. Nothing is added to the history.
+    pub fn apply_partial_feedback(&self, pos: usize, ch: char,
+                                  state: LetterState) -> Result<()>
+    {
+        if pos < 1 || pos > self.length {
+            let msg = format!("Position {} must be between 1 and {}.", pos,
+                              self.length);
+            return Err(Error::new(&msg));
+        }
+
+        let ch = ch.to_lowercase().next().unwrap_or(ch);
+        if state == LetterState::Correct {
+            self.check_green(pos, ch)?;
+        }
+
+        (*self.tried.borrow_mut()).insert(ch);
+        match state {
+            LetterState::Correct => self.set_char_position(pos, ch),
+
+            LetterState::Present => {
+                self.include_char(ch);
+                self.forbid_char_at(ch, pos-1);
+            },
+
+            LetterState::Absent => {
+                if self.is_known_char(ch) {
+                    self.forbid_char_at(ch, pos-1);
+                } else {
+                    self.exclude_char(ch);
+                }
+            }
+        }
+
         *self.matches.borrow_mut() = None;
+        Ok(())
     }
 
-    fn remove_included_char(&self, ch: char) {
-        (*self.include.borrow_mut()).remove(&ch);
+    pub fn state(&self) -> DictionaryState {
+        let positions = self.positions.borrow();
+        let allowed = self.allowed.borrow().iter().enumerate()
+            .filter(|(_, letters)| !letters.is_empty())
+            .map(|(i, letters)| {
+                let mut letters = letters.iter().copied().collect::<Vec<_>>();
+                letters.sort();
+                (i + 1, letters)
+            })
+            .collect();
+        let intersection = self.intersection.borrow().as_ref()
+            .map(|words| {
+                let mut words = words.iter().cloned().collect::<Vec<_>>();
+                words.sort();
+                words
+            });
+
+        DictionaryState {
+            include: self.included_chars(),
+            exclude: self.excluded_chars(),
+            positions: match positions.iter().all(|ch| *ch == '.') {
+                true => None,
+                false => Some(positions.clone())
+            },
+            allowed,
+            not_at: position_map(&self.not_at.borrow()),
+            present: position_map(&self.present.borrow()),
+            min_counts: self.min_counts.borrow().iter()
+                .map(|(ch, count)| (*ch, *count)).collect(),
+            parity: self.parity.borrow().iter()
+                .map(|(ch, even)| (*ch, *even)).collect(),
+            adjacent: self.forbidden_adjacent(),
+            multiset: self.letter_multiset(),
+            intersection,
+            tried: self.tried_chars(),
+            history: self.history.borrow().clone()
+        }
+    }
+
+    // Unlike `apply_state`, restoring puts back every constraint along with
+    // the history and tried letters, so a snapshot can be taken and
+    // restored around an experiment.
+    pub fn snapshot(&self) -> DictionaryState {
+        self.state()
+    }
+
+    pub fn restore(&self, state: DictionaryState) -> Result<()> {
+        let positions = match state.positions {
+            Some(positions) => positions,
+            None => vec!['.'; self.length]
+        };
+        if positions.len() != self.length {
+            let msg = format!("Position pattern must be {} characters.",
+                              self.length);
+            return Err(Error::new(&msg));
+        }
+
+        let indices = state.allowed.keys()
+            .chain(state.not_at.values().flatten())
+            .chain(state.present.values().flatten());
+        for pos in indices {
+            if *pos < 1 || *pos > self.length {
+                let msg = format!("Position {} must be between 1 and {}.",
+                                  pos, self.length);
+                return Err(Error::new(&msg));
+            }
+        }
+
+        let mut allowed = vec![HashSet::new(); self.length];
+        for (pos, letters) in state.allowed {
+            allowed[pos-1] = letters.into_iter().collect();
+        }
+
+        *self.include.borrow_mut() = state.include.into_iter().collect();
+        *self.exclude.borrow_mut() = state.exclude.into_iter().collect();
+        *self.positions.borrow_mut() = positions;
+        *self.allowed.borrow_mut() = allowed;
+        *self.not_at.borrow_mut() = index_map(state.not_at);
+        *self.present.borrow_mut() = index_map(state.present);
+        *self.min_counts.borrow_mut() = state.min_counts.into_iter().collect();
+        *self.parity.borrow_mut() = state.parity.into_iter().collect();
+        *self.adjacent.borrow_mut() = state.adjacent.into_iter().collect();
+        *self.multiset.borrow_mut() = state.multiset
+            .map(|letters| letters.chars().collect());
+        *self.intersection.borrow_mut() = state.intersection
+            .map(|words| words.into_iter().collect());
+        *self.tried.borrow_mut() = state.tried.into_iter().collect();
+        *self.history.borrow_mut() = state.history;
         *self.matches.borrow_mut() = None;
+        Ok(())
     }
 
-    fn clear_excluded_chars(&self) {
-        (*self.exclude.borrow_mut()).clear();
+    pub fn apply_state(&self, state: &DictionaryState) -> Result<()> {
+        if let Some(positions) = &state.positions {
+            if positions.len() != self.length {
+                let msg = format!("Position pattern must be {} characters.",
+                                  self.length);
+                return Err(Error::new(&msg));
+            }
+        }
+
+        let letters = state.include.iter()
+            .chain(state.exclude.iter())
+            .chain(state.positions.iter().flatten().filter(|ch| **ch != '.'));
+        for ch in letters {
+            if !self.is_letter(*ch) {
+                let msg = format!("'{}' is not a valid character.", ch);
+                return Err(Error::new(&msg));
+            }
+        }
+
+        self.reset();
+        for ch in &state.exclude {
+            self.add_char(SetType::Excluded, *ch);
+        }
+        for ch in &state.include {
+            self.add_char(SetType::Included, *ch);
+        }
+        if let Some(positions) = &state.positions {
+            for (i, ch) in positions.iter().enumerate() {
+                if *ch != '.' {
+                    self.set_char_position(i+1, *ch);
+                }
+            }
+        }
+        Ok(())
     }
 
-    fn clear_included_chars(&self) {
-        (*self.include.borrow_mut()).clear();
+    pub fn describe(&self) -> String {
+        let allowed = self.allowed.borrow();
+        let greens = self.positions.borrow().iter()
+            .zip(allowed.iter())
+            .map(|(ch, letters)| {
+                if *ch != '.' || letters.is_empty() {
+                    return ch.to_uppercase().to_string();
+                }
+                let mut letters = letters.iter().copied().collect::<Vec<_>>();
+                letters.sort();
+                let letters = letters.into_iter().collect::<String>();
+                format!("[{}]", letters.to_uppercase())
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut not_at = self.not_at.borrow().clone();
+        for (ch, indices) in &*self.present.borrow() {
+            not_at.entry(*ch).or_default().extend(indices);
+        }
+        let mut letters = not_at.keys().copied().collect::<Vec<_>>();
+        letters.sort();
+        let not_at = letters.iter()
+            .map(|ch| {
+                let mut indices = not_at[ch].iter()
+                    .map(|i| i + 1).collect::<Vec<_>>();
+                indices.sort();
+                let indices = indices.iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>();
+                format!("{} {}", ch.to_uppercase(), indices.join(", "))
+            })
+            .collect::<Vec<_>>();
+        let not_at = match not_at.is_empty() {
+            true => String::from("none"),
+            false => not_at.join("; ")
+        };
+
+        let mut yellows = self.included_chars();
+        yellows.extend(self.present.borrow().keys());
+        yellows.sort();
+        yellows.dedup();
+
+        format!("Greens: {}\nYellows: {}\nGrays: {}\nNot at: {}",
+                greens,
+                describe_chars(&yellows),
+                describe_chars(&self.excluded_chars()),
+                not_at)
     }
 
-    fn match_excluded(&self, s: &str) -> bool {
-        let exclude = self.exclude.borrow();
+    pub fn history(&self) -> Ref<'_, Vec<(String, Feedback)>> {
+        self.history.borrow()
+    }
 
-        for ch in &*exclude {
-            if s.contains(*ch) {
-                return true;
+    pub fn export_emoji_grid(&self) -> String {
+        let history = self.history.borrow();
+        let rows: Vec<String> = history.iter()
+            .map(|(_, feedback)| feedback.to_emoji())
+            .collect();
+        rows.join("\n")
+    }
+
+    // One row per guess with a column for each letter and its state.
+    pub fn export_markdown(&self) -> String {
+        let history = self.history.borrow();
+        if history.is_empty() {
+            return String::new();
+        }
+
+        let mut header = String::from("| # |");
+        let mut rule = String::from("|---|");
+        for pos in 1..=self.length {
+            header.push_str(&format!(" {} |", pos));
+            rule.push_str("---|");
+        }
+
+        let mut rows = vec![header, rule];
+        for (i, (guess, feedback)) in history.iter().enumerate() {
+            let mut row = format!("| {} |", i + 1);
+            for (ch, state) in guess.chars().zip(feedback.states()) {
+                row.push_str(&format!(" {} {} |", ch.to_uppercase(),
+                                      state.to_emoji()));
             }
+            rows.push(row);
         }
-        return false;
+        rows.join("\n")
     }
 
-    fn match_included(&self, s: &str) -> bool {
+    pub fn keep_greens(&self) -> bool {
+        *self.keep_greens.borrow()
+    }
+
+    // When enabled, a letter that is both known at an exact position and
+    // excluded is only excluded from the remaining positions, so a green
+    // letter with grays elsewhere matches words where it appears exactly
+    // where it is green.
+    pub fn set_keep_greens(&self, keep_greens: bool) {
+        *self.keep_greens.borrow_mut() = keep_greens;
+        *self.matches.borrow_mut() = None;
+    }
+
+    pub fn hide_guessed(&self) -> bool {
+        *self.hide_guessed.borrow()
+    }
+
+    // Words already in the history are left out of the matches, even the
+    // one that was guessed correctly.
+    pub fn set_hide_guessed(&self, hide_guessed: bool) {
+        *self.hide_guessed.borrow_mut() = hide_guessed;
+        *self.matches.borrow_mut() = None;
+    }
+
+    pub fn is_enabled(&self, constraint: Constraint) -> bool {
+        !self.disabled.borrow().contains(&constraint)
+    }
+
+    pub fn set_enabled(&self, constraint: Constraint, enabled: bool) {
+        if enabled {
+            (*self.disabled.borrow_mut()).remove(&constraint);
+        } else {
+            (*self.disabled.borrow_mut()).insert(constraint);
+        }
+        *self.matches.borrow_mut() = None;
+    }
+
+    pub fn is_satisfiable(&self) -> bool {
+        self.contradiction().is_none()
+    }
+
+    // Checks the enabled constraints against each other without looking at
+    // the word list, so an impossible query can be told apart from one that
+    // simply matches no words.
+    pub fn contradiction(&self) -> Option<String> {
+        let enabled = |constraint| self.is_enabled(constraint);
+        let positions = match enabled(Constraint::Positions) {
+            true => self.positions.borrow().clone(),
+            false => vec!['.'; self.length]
+        };
+        let slots = match enabled(Constraint::Positions) {
+            true => self.allowed.borrow().clone(),
+            false => vec![HashSet::new(); self.length]
+        };
         let include = self.include.borrow();
+        let exclude = self.exclude.borrow();
+        let not_at = self.not_at.borrow();
+        let present = self.present.borrow();
+        let min_counts = self.min_counts.borrow();
+        let parity = self.parity.borrow();
 
-        for ch in &*include {
-            if s.contains(*ch) { continue; }
-            return false;
+        if let Some(multiset) = &*self.multiset.borrow() {
+            if multiset.len() != self.length {
+                return Some(format!("the letter multiset has {} letters but \
+                                     words have {}", multiset.len(),
+                                    self.length));
+            }
+        }
+
+        for (i, letters) in slots.iter().enumerate() {
+            let excluded = enabled(Constraint::Excluded) &&
+                !letters.is_empty() &&
+                letters.iter().all(|ch| exclude.contains(ch));
+            if excluded && positions[i] == '.' {
+                return Some(format!("position {} only allows excluded \
+                                     letters", i + 1));
+            }
+        }
+
+        let mut letters: HashSet<char> = positions.iter()
+            .filter(|ch| **ch != '.').cloned().collect();
+        letters.extend(include.iter());
+        letters.extend(present.keys());
+        letters.extend(exclude.iter());
+        letters.extend(min_counts.keys());
+        letters.extend(parity.keys());
+        let mut letters = letters.into_iter().collect::<Vec<_>>();
+        letters.sort();
+
+        let open = positions.iter().filter(|ch| **ch == '.').count();
+        let mut needed = 0;
+        for ch in letters {
+            let greens = positions.iter().filter(|c| **c == ch).count();
+            let excluded = enabled(Constraint::Excluded) &&
+                exclude.contains(&ch);
+            if excluded && greens > 0 && !self.keep_greens() {
+                let pos = positions.iter().position(|c| *c == ch).unwrap();
+                return Some(format!("'{}' is excluded but is correct at \
+                                     position {}", ch, pos + 1));
+            }
+
+            let mut need = greens;
+            if enabled(Constraint::Included) && include.contains(&ch) {
+                need = need.max(1);
+            }
+            if enabled(Constraint::Misplaced) && present.contains_key(&ch) {
+                need = need.max(1);
+            }
+            if enabled(Constraint::Counts) {
+                need = need.max(*min_counts.get(&ch).unwrap_or(&0));
+            }
+
+            let misplaced = |i: &usize| {
+                enabled(Constraint::Misplaced) &&
+                    [&*not_at, &*present].iter().any(|map| {
+                        map.get(&ch).is_some_and(|set| set.contains(i))
+                    })
+            };
+            let free = positions.iter().enumerate()
+                .filter(|(i, c)| {
+                    **c == '.' &&
+                        (slots[*i].is_empty() || slots[*i].contains(&ch)) &&
+                        !misplaced(i)
+                })
+                .count();
+            let allowed = match excluded {
+                true => greens,
+                false => greens + free
+            };
+
+            if enabled(Constraint::Counts) {
+                if let Some(even) = parity.get(&ch) {
+                    if (need % 2 == 0) != *even {
+                        need += 1;
+                    }
+                    if !*even && allowed == 0 {
+                        return Some(format!("'{}' must appear an odd number \
+                                             of times but is excluded", ch));
+                    }
+                }
+            }
+
+            if need > allowed {
+                let msg = if excluded {
+                    format!("'{}' is both required and excluded", ch)
+                } else if allowed == 0 {
+                    format!("'{}' is required but has no position left", ch)
+                } else {
+                    format!("'{}' must appear {} times but fits in at most \
+                             {}", ch, need, allowed)
+                };
+                return Some(msg);
+            }
+            needed += need - greens;
+        }
+
+        match needed > open {
+            true => Some(format!("{} required letters do not fit in {} open \
+                                  positions", needed, open)),
+            false => None
         }
-        return true;
     }
 
-    fn match_positions(&self, s: &str) -> bool {
-        let positions = self.positions.borrow();
+    pub fn sort_mode(&self) -> SortMode {
+        *self.sort_mode.borrow()
+    }
 
-        for (i, ch) in s.char_indices() {
-            if positions[i] == ch { continue; }
-            if positions[i] == '.' { continue; }
-            return false;
+    pub fn set_sort_mode(&self, sort_mode: SortMode) {
+        *self.sort_mode.borrow_mut() = sort_mode;
+        *self.matches.borrow_mut() = None;
+    }
+
+    pub fn matches(&self) -> Ref<Option<Vec<String>>> {
+        let mut matches = self.matches.borrow_mut();
+        let mut filtered = match &*matches {
+            Some(matches) => self.filter_matches(matches, None),
+            None          => self.filter_matches(&self.words.borrow(), None),
+        };
+        self.sort_matches(&mut filtered);
+        *matches = Some(filtered);
+        drop(matches);
+
+        self.matches.borrow()
+    }
+
+    pub fn remaining(&self) -> Ref<'_, [String]> {
+        Ref::map(self.matches(), |matches| {
+            matches.as_deref().unwrap_or(&[])
+        })
+    }
+
+    pub fn matches_stream(&self, batch: usize) -> MatchStream {
+        if batch == 0 {
+            panic!("`batch` must be greater than 0.")
+        }
+
+        let words = match &*self.matches.borrow() {
+            Some(matches) => matches.clone(),
+            None          => self.words.borrow().clone()
+        };
+        MatchStream {
+            words,
+            position: 0,
+            batch,
+            matches: Vec::new(),
+            finished: false
         }
-        return true;
     }
-}
 
-fn read_words(database: &str) -> io::Result<Vec<String>> {
-    let file = File::open(database)?;
-    let reader = BufReader::new(file);
-    let mut matches = Vec::new();
+    pub fn matches_paged(&self, page: usize, per_page: usize)
+        -> (Ref<'_, [String]>, usize)
+    {
+        if per_page == 0 {
+            panic!("`per_page` must be greater than 0.")
+        }
 
-    for line in reader.lines() {
-        let line = line?;
-        if line.len() == 5 {
-            matches.push(line.to_lowercase());
+        let matches = self.remaining();
+        let pages = matches.len().div_ceil(per_page);
+
+        let slice = Ref::map(matches, |matches| {
+            let start = (page * per_page).min(matches.len());
+            let end = (start + per_page).min(matches.len());
+            &matches[start..end]
+        });
+        (slice, pages)
+    }
+
+    pub fn matches_with_prefix(&self, typed: &str) -> Vec<String> {
+        let typed = typed.to_lowercase();
+        self.remaining().iter()
+            .filter(|word| word.starts_with(&typed))
+            .cloned().collect()
+    }
+
+    // Each letter of `partial` is tried as a green at its position without
+    // being committed; '.' leaves a position open.
+    pub fn preview_positions(&self, partial: &str) -> Vec<String> {
+        let partial = partial.to_lowercase().chars().collect::<Vec<_>>();
+        self.remaining().iter()
+            .filter(|word| {
+                word.chars().zip(&partial)
+                    .all(|(ch, green)| *green == '.' || ch == *green)
+            })
+            .cloned().collect()
+    }
+
+    pub fn matches_answers_only(&self) -> Vec<String> {
+        let matches = self.remaining();
+
+        match &*self.answers.borrow() {
+            Some(answers) => matches.iter()
+                .filter(|word| answers.contains(*word))
+                .cloned().collect(),
+            None => matches.to_vec()
         }
     }
 
-    Ok(matches)
+    pub fn matches_with_stats(&self) -> (Vec<String>, FilterStats) {
+        let words = self.words.borrow();
+        let mut stats = FilterStats {
+            candidates: words.len(),
+            ..FilterStats::default()
+        };
+
+        let mut matches = self.filter_matches(&words, Some(&mut stats));
+        if let Some(answers) = &*self.answers.borrow() {
+            let total = matches.len();
+            matches.retain(|word| answers.contains(word));
+            stats.answers = total - matches.len();
+        }
+        self.sort_matches(&mut matches);
+        stats.remaining = matches.len();
+        (matches, stats)
+    }
+
+    pub fn non_matches(&self) -> Vec<String> {
+        let mut rejected: Vec<String> = self.words.borrow().iter()
+            .filter(|s| !self.is_match(s))
+            .cloned().collect();
+        self.sort_matches(&mut rejected);
+        rejected
+    }
+
+    pub fn position_letter_matrix(&self) -> Vec<[usize; 26]> {
+        let mut matrix = vec![[0; 26]; self.length];
+        for word in self.remaining().iter() {
+            for (i, ch) in word.chars().enumerate().take(self.length) {
+                if ch.is_ascii_lowercase() {
+                    matrix[i][(ch as u8 - b'a') as usize] += 1;
+                }
+            }
+        }
+        matrix
+    }
+
+    pub fn intersect(&self, other: &Dictionary) -> Result<()> {
+        if self.length != other.length {
+            return Err(Error::new("Dictionaries must have the same length."));
+        }
+
+        let theirs: HashSet<String> = other.remaining().iter()
+            .cloned().collect();
+
+        let mut intersection = self.intersection.borrow_mut();
+        let theirs = match intersection.take() {
+            Some(ours) => ours.intersection(&theirs).cloned().collect(),
+            None => theirs
+        };
+        *intersection = Some(theirs);
+        drop(intersection);
+
+        self.matches();
+        Ok(())
+    }
+
+    pub fn expected_information(&self, guess: &str) -> f64 {
+        let guess = guess.to_lowercase();
+        entropy(&guess, &self.remaining())
+    }
+
+    pub fn suggest_guess(&self) -> Option<String> {
+        self.suggest_guess_with(TieBreak::Candidates)
+    }
+
+    // Entropies are summed in hash order, so equal splits can differ in
+    // the last few bits and are compared with a small tolerance.
+    pub fn suggest_guess_with(&self, tie_break: TieBreak) -> Option<String> {
+        let matches = self.remaining();
+        if matches.len() <= 2 {
+            return matches.first().cloned();
+        }
+
+        let candidates: HashSet<&str> = matches.iter()
+            .map(String::as_str).collect();
+        let words = self.words.borrow();
+        let mut best: Option<(f64, bool, &String)> = None;
+        for word in words.iter() {
+            let bits = entropy(word, &matches);
+            let candidate = tie_break == TieBreak::Candidates &&
+                candidates.contains(word.as_str());
+            let better = match best {
+                None => true,
+                Some((best_bits, _, _)) if bits > best_bits + TIE_EPSILON => {
+                    true
+                },
+                Some((best_bits, best_candidate, best_word)) => {
+                    bits >= best_bits - TIE_EPSILON &&
+                        (candidate, Reverse(word)) >
+                        (best_candidate, Reverse(best_word))
+                }
+            };
+
+            if better {
+                best = Some((bits, candidate, word));
+            }
+        }
+        best.map(|(_, _, word)| word.clone())
+    }
+
+    // Each distinct letter scores the number of candidates containing it,
+    // so words covering more of the common letters win.
+    pub fn suggest(&self) -> Option<String> {
+        let matches = self.remaining();
+        let freqs = letter_frequencies(&matches);
+        matches.iter()
+            .max_by_key(|word| (score_word(word, &freqs), Reverse(*word)))
+            .cloned()
+    }
+
+    pub fn worst_case(&self, guess: &str) -> usize {
+        let guess = guess.to_lowercase();
+        worst_case(&guess, &self.remaining())
+    }
+
+    pub fn compare_guesses(&self, a: &str, b: &str) -> GuessComparison {
+        let matches = self.remaining();
+        let metrics = |guess: &str| {
+            let guess = guess.to_lowercase();
+            GuessMetrics {
+                entropy: entropy(&guess, &matches),
+                worst_case: worst_case(&guess, &matches),
+                expected_remaining: expected_remaining(&guess, &matches),
+                guess
+            }
+        };
+        GuessComparison { first: metrics(a), second: metrics(b) }
+    }
+
+    pub fn suggest_minimax(&self) -> Option<String> {
+        let matches = self.remaining();
+        if matches.len() <= 2 {
+            return matches.first().cloned();
+        }
+
+        let candidates: HashSet<&str> = matches.iter()
+            .map(String::as_str).collect();
+        let words = self.words.borrow();
+        words.iter()
+            .min_by_key(|word| {
+                (worst_case(word, &matches),
+                 !candidates.contains(word.as_str()))
+            })
+            .cloned()
+    }
+
+    // Letters found in every candidate or in none of them tell us nothing,
+    // so only untried letters that split the candidates are counted.
+    pub fn suggest_explorer(&self) -> Option<String> {
+        let matches = self.remaining();
+        if matches.len() <= 2 {
+            return matches.first().cloned();
+        }
+
+        let mut useful: HashMap<char, usize> = HashMap::new();
+        for word in matches.iter() {
+            let mut letters = word.chars().collect::<Vec<_>>();
+            letters.sort();
+            letters.dedup();
+            for ch in letters {
+                *useful.entry(ch).or_insert(0) += 1;
+            }
+        }
+        let tried = self.tried.borrow();
+        useful.retain(|ch, count| {
+            !tried.contains(ch) && *count < matches.len()
+        });
+
+        let candidates: HashSet<&str> = matches.iter()
+            .map(String::as_str).collect();
+        let words = self.words.borrow();
+        words.iter()
+            .max_by_key(|word| {
+                let mut letters = word.chars().collect::<Vec<_>>();
+                letters.sort();
+                letters.dedup();
+                let score = letters.iter()
+                    .filter_map(|ch| useful.get(ch))
+                    .sum::<usize>();
+                (score, candidates.contains(word.as_str()))
+            })
+            .cloned()
+    }
+
+    // A letter scores higher the more candidates contain it and the more
+    // positions it is spread across, since finding it then says more.
+    pub fn letters_by_usefulness(&self) -> Vec<(char, f64)> {
+        let matches = self.remaining();
+        if matches.is_empty() {
+            return Vec::new();
+        }
+
+        let mut known = self.tried.borrow().clone();
+        known.extend(self.include.borrow().iter());
+        known.extend(self.exclude.borrow().iter());
+        known.extend(self.positions.borrow().iter().filter(|ch| **ch != '.'));
+
+        let freqs = letter_frequencies(&matches);
+        let positions = position_frequencies(&matches, self.length);
+        let mut letters = freqs.into_iter()
+            .filter(|(ch, _)| !known.contains(ch))
+            .map(|(ch, count)| {
+                let frequency = count as f64 / matches.len() as f64;
+                let spread = positions.iter()
+                    .filter(|freqs| freqs.contains_key(&ch))
+                    .count() as f64 / self.length as f64;
+                (ch, frequency * (1.0 + spread) / 2.0)
+            })
+            .collect::<Vec<_>>();
+        letters.sort_by(|a, b| {
+            b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0))
+        });
+        letters
+    }
+
+    pub fn rank_by_elimination(&self) -> Vec<(String, usize)> {
+        let matches = self.remaining();
+        let mut ranked = matches.iter()
+            .map(|word| (word.clone(), eliminated(word, &matches)))
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    // Candidates keyed by the most words they could leave standing.
+    pub fn guesses_by_bucket(&self) -> BTreeMap<usize, Vec<String>> {
+        let matches = self.remaining();
+        let mut buckets: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for word in matches.iter() {
+            buckets.entry(worst_case(word, &matches))
+                .or_default()
+                .push(word.clone());
+        }
+        for words in buckets.values_mut() {
+            words.sort();
+        }
+        buckets
+    }
+
+    // Likelihoods come from the frequency list when there is one, and
+    // otherwise the answer list serves as the prior. Words outside the answer
+    // list can't be the answer at all, so once no answers are left there are
+    // no candidates to annotate. When none of the answers has a frequency
+    // they are all taken to be equally likely.
+    pub fn annotated_matches(&self) -> Vec<MatchInfo> {
+        let answers = self.matches_answers_only();
+        if answers.is_empty() {
+            return Vec::new();
+        }
+        let matches = self.remaining().to_vec();
+
+        let mut weights: HashMap<&str, f64> = HashMap::new();
+        if let Some(frequencies) = &*self.frequencies.borrow() {
+            for word in &answers {
+                let count = frequencies.get(word).copied().unwrap_or(0);
+                weights.insert(word, count as f64);
+            }
+        }
+        if weights.values().all(|weight| *weight == 0.0) {
+            weights = answers.iter().map(|word| (word.as_str(), 1.0))
+                .collect();
+        }
+        let total = weights.values().sum::<f64>();
+
+        let mut annotated = matches.iter()
+            .map(|word| {
+                let likelihood = weights.get(word.as_str())
+                    .map_or(0.0, |weight| weight / total);
+                MatchInfo {
+                    word: word.clone(),
+                    likelihood,
+                    expected_guesses: expected_guesses(word, &answers)
+                }
+            })
+            .collect::<Vec<_>>();
+        annotated.sort_by(|a, b| {
+            a.expected_guesses.total_cmp(&b.expected_guesses)
+                .then_with(|| b.likelihood.total_cmp(&a.likelihood))
+                .then_with(|| a.word.cmp(&b.word))
+        });
+        annotated
+    }
+
+    pub fn build_decision_tree(&self) -> Result<GuessTree> {
+        GuessTree::build(&self.matches_answers_only())
+    }
+
+    // Rare letters and a large family of one-letter variants (the "_ight"
+    // problem) both make an answer harder to find.
+    pub fn difficulty_score(&self, word: &str) -> f64 {
+        let word = word.to_lowercase();
+        let words = self.words.borrow();
+        if words.is_empty() {
+            return 0.0;
+        }
+
+        let freqs = letter_frequencies(&words);
+        let chars: HashSet<char> = word.chars().collect();
+        let rarity = chars.iter()
+            .map(|ch| {
+                let freq = *freqs.get(ch).unwrap_or(&0) as f64;
+                1.0 - freq / words.len() as f64
+            })
+            .sum::<f64>() / chars.len().max(1) as f64;
+
+        let neighbours = words.iter()
+            .filter(|other| is_neighbour(&word, other))
+            .count();
+
+        rarity * 5.0 + (1.0 + neighbours as f64).log2()
+    }
+
+    pub fn save_matches<P: AsRef<Path>>(&self, path: P) -> Result<usize> {
+        let matches = self.remaining();
+
+        let mut file = File::create(path)?;
+        for word in matches.iter() {
+            writeln!(file, "{}", word)?;
+        }
+        Ok(matches.len())
+    }
+
+    pub fn position_letter_counts(&self, pos: usize) -> Vec<(char, usize)> {
+        if pos < 1 || pos > self.length {
+            panic!("`pos` must be between 1 and {}.", self.length)
+        }
+
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for word in self.remaining().iter() {
+            if let Some(ch) = word.chars().nth(pos-1) {
+                *counts.entry(ch).or_insert(0) += 1;
+            }
+        }
+
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        counts.sort_by_key(|(ch, count)| (Reverse(*count), *ch));
+        counts
+    }
+
+    pub fn possible_letters_at(&self, pos: usize) -> Vec<char> {
+        let mut letters = self.position_letter_counts(pos).into_iter()
+            .map(|(ch, _)| ch)
+            .collect::<Vec<_>>();
+        letters.sort();
+        letters
+    }
+
+    pub fn candidates_after(&self, guess: &str, feedback: &Feedback) -> usize {
+        let guess = guess.to_lowercase();
+        self.remaining().iter()
+            .filter(|word| Feedback::compute(&guess, word) == *feedback)
+            .count()
+    }
+
+    fn sort_matches(&self, matches: &mut [String]) {
+        match self.sort_mode() {
+            SortMode::Dictionary => (),
+            SortMode::Alphabetical => matches.sort(),
+
+            SortMode::ByFrequency => {
+                let freqs = position_frequencies(matches, self.length);
+                sort_by_score(matches, |word| {
+                    word.chars().enumerate()
+                        .map(|(i, ch)| freqs[i].get(&ch).unwrap_or(&0))
+                        .sum()
+                });
+            },
+
+            SortMode::ByScore => {
+                let freqs = letter_frequencies(matches);
+                sort_by_score(matches, |word| score_word(word, &freqs));
+            }
+        }
+    }
+
+    fn filter_matches(&self, matches: &[String],
+                      mut stats: Option<&mut FilterStats>) -> Vec<String>
+    {
+        let history = self.history.borrow();
+        let guessed: HashSet<&str> = match self.hide_guessed() {
+            true => history.iter().map(|(guess, _)| guess.as_str()).collect(),
+            false => HashSet::new()
+        };
+
+        matches.iter()
+            .filter(|s| !guessed.contains(s.as_str()))
+            .filter(|s| match self.rejected_by(s) {
+                Some(stage) => {
+                    if let Some(stats) = stats.as_deref_mut() {
+                        stats.record(stage);
+                    }
+                    false
+                },
+                None => true
+            })
+            .cloned().collect()
+    }
+
+    pub fn is_match(&self, s: &str) -> bool {
+        s.chars().count() == self.length && self.rejected_by(s).is_none()
+    }
+
+    fn rejected_by(&self, s: &str) -> Option<FilterStage> {
+        let enabled = |constraint| self.is_enabled(constraint);
+
+        if enabled(Constraint::Excluded) && self.match_excluded(s) {
+            Some(FilterStage::Excluded)
+        } else if enabled(Constraint::Misplaced) &&
+            (self.match_not_at(s) || !self.match_present(s))
+        {
+            Some(FilterStage::Misplaced)
+        } else if !self.match_intersection(s) {
+            Some(FilterStage::Intersection)
+        } else if enabled(Constraint::Included) && !self.match_included(s) {
+            Some(FilterStage::Included)
+        } else if enabled(Constraint::Positions) && !self.match_positions(s) {
+            Some(FilterStage::Positions)
+        } else if enabled(Constraint::Counts) && !self.match_counts(s) {
+            Some(FilterStage::Counts)
+        } else if self.match_adjacent(s) {
+            Some(FilterStage::Adjacent)
+        } else if !self.match_multiset(s) {
+            Some(FilterStage::Multiset)
+        } else {
+            None
+        }
+    }
+
+    // The cached matches were filtered with every constraint so far, so
+    // they're only reused when a change can't let more words back in.
+    fn exclude_char(&self, ch: char) {
+        if (*self.include.borrow_mut()).remove(&ch) {
+            *self.matches.borrow_mut() = None;
+        }
+        (*self.exclude.borrow_mut()).insert(ch);
+    }
+
+    fn include_char(&self, ch: char) {
+        if (*self.exclude.borrow_mut()).remove(&ch) {
+            *self.matches.borrow_mut() = None;
+        }
+        (*self.include.borrow_mut()).insert(ch);
+    }
+
+    fn remove_excluded_char(&self, ch: char) {
+        (*self.exclude.borrow_mut()).remove(&ch);
+        *self.matches.borrow_mut() = None;
+    }
+
+    fn remove_included_char(&self, ch: char) {
+        (*self.include.borrow_mut()).remove(&ch);
+        *self.matches.borrow_mut() = None;
+    }
+
+    fn clear_excluded_chars(&self) {
+        (*self.exclude.borrow_mut()).clear();
+        *self.matches.borrow_mut() = None;
+    }
+
+    fn clear_included_chars(&self) {
+        (*self.include.borrow_mut()).clear();
+        *self.matches.borrow_mut() = None;
+    }
+
+    fn forbid_char_at(&self, ch: char, index: usize) {
+        (*self.not_at.borrow_mut()).entry(ch)
+            .or_default()
+            .insert(index);
+    }
+
+    fn check_green(&self, pos: usize, ch: char) -> Result<()> {
+        match self.char_position(pos) {
+            Some(green) if ch != '.' && green != ch => {
+                let msg = format!("Position {} is already '{}'; refusing to \
+                                   overwrite it with '{}'.", pos, green, ch);
+                Err(Error::new(&msg))
+            },
+            _ => Ok(())
+        }
+    }
+
+    fn is_known_char(&self, ch: char) -> bool {
+        self.include.borrow().contains(&ch) ||
+            self.present.borrow().contains_key(&ch) ||
+            self.positions.borrow().contains(&ch)
+    }
+
+    fn match_not_at(&self, s: &str) -> bool {
+        let not_at = self.not_at.borrow();
+
+        for (i, ch) in s.chars().enumerate() {
+            if let Some(indices) = not_at.get(&ch) {
+                if indices.contains(&i) { return true; }
+            }
+        }
+        return false;
+    }
+
+    fn match_present(&self, s: &str) -> bool {
+        let present = self.present.borrow();
+
+        for (ch, indices) in &*present {
+            if !s.contains(*ch) { return false; }
+            for (i, c) in s.chars().enumerate() {
+                if c == *ch && indices.contains(&i) { return false; }
+            }
+        }
+        true
+    }
+
+    fn match_excluded(&self, s: &str) -> bool {
+        let exclude = self.exclude.borrow();
+        let positions = self.positions.borrow();
+        let keep_greens = self.keep_greens();
+
+        for ch in &*exclude {
+            if keep_greens && positions.contains(ch) {
+                let elsewhere = s.chars().enumerate()
+                    .any(|(i, c)| c == *ch && positions[i] != *ch);
+                if elsewhere { return true; }
+                continue;
+            }
+
+            if s.contains(*ch) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    fn match_intersection(&self, s: &str) -> bool {
+        match &*self.intersection.borrow() {
+            Some(words) => words.contains(s),
+            None => true
+        }
+    }
+
+    fn match_counts(&self, s: &str) -> bool {
+        let min_counts = self.min_counts.borrow();
+
+        for (ch, count) in &*min_counts {
+            if s.chars().filter(|c| c == ch).count() < *count {
+                return false;
+            }
+        }
+
+        let parity = self.parity.borrow();
+        for (ch, even) in &*parity {
+            let count = s.chars().filter(|c| c == ch).count();
+            if (count % 2 == 0) != *even {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn match_adjacent(&self, s: &str) -> bool {
+        let adjacent = self.adjacent.borrow();
+        if adjacent.is_empty() {
+            return false;
+        }
+
+        s.chars().zip(s.chars().skip(1))
+            .any(|(a, b)| adjacent.contains(&adjacent_pair(a, b)))
+    }
+
+    fn match_multiset(&self, s: &str) -> bool {
+        match &*self.multiset.borrow() {
+            Some(multiset) => {
+                let mut letters = s.chars().collect::<Vec<_>>();
+                letters.sort();
+                letters == *multiset
+            },
+            None => true
+        }
+    }
+
+    fn match_included(&self, s: &str) -> bool {
+        let include = self.include.borrow();
+
+        for ch in &*include {
+            if s.contains(*ch) { continue; }
+            return false;
+        }
+        return true;
+    }
+
+    fn match_positions(&self, s: &str) -> bool {
+        let positions = self.positions.borrow();
+        let allowed = self.allowed.borrow();
+
+        for (letters, ch) in allowed.iter().zip(s.chars()) {
+            if !letters.is_empty() && !letters.contains(&ch) {
+                return false;
+            }
+        }
+
+        for (i, ch) in s.chars().enumerate() {
+            if positions[i] == ch { continue; }
+            if positions[i] == '.' { continue; }
+            return false;
+        }
+        return true;
+    }
+}
+
+// Each line holds a word and its count separated by a tab, as in the usual
+// unigram frequency lists; blank lines are skipped.
+pub fn read_frequencies<P: AsRef<Path>>(path: P)
+    -> Result<HashMap<String, u64>>
+{
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|error| {
+        let msg = format!("Unable to read {}: {}.", path.display(), error);
+        Error::new(&msg)
+    })?;
+
+    let mut frequencies = HashMap::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let count = line.split_once('\t')
+            .and_then(|(word, count)| {
+                let count = count.trim().parse::<u64>().ok()?;
+                Some((word.trim().to_lowercase(), count))
+            });
+        match count {
+            Some((word, count)) => { frequencies.insert(word, count); },
+            None => {
+                let msg = format!("Invalid frequency on line {} of {}.",
+                                  i + 1, path.display());
+                return Err(Error::new(&msg));
+            }
+        }
+    }
+    Ok(frequencies)
+}
+
+pub fn matrix_to_csv(matrix: &[[usize; 26]]) -> String {
+    let mut csv = String::from("position");
+    for ch in 'a'..='z' {
+        csv.push(',');
+        csv.push(ch);
+    }
+    csv.push('\n');
+
+    for (i, row) in matrix.iter().enumerate() {
+        csv.push_str(&(i+1).to_string());
+        for count in row {
+            csv.push(',');
+            csv.push_str(&count.to_string());
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+fn adjacent_pair(a: char, b: char) -> (char, char) {
+    match a <= b {
+        true => (a, b),
+        false => (b, a)
+    }
+}
+
+// Forbidden positions are kept 0-based; states use 1-based positions.
+fn position_map(map: &HashMap<char, HashSet<usize>>)
+    -> BTreeMap<char, Vec<usize>>
+{
+    map.iter()
+        .map(|(ch, indices)| {
+            let mut positions = indices.iter()
+                .map(|i| i + 1).collect::<Vec<_>>();
+            positions.sort();
+            (*ch, positions)
+        })
+        .collect()
+}
+
+fn index_map(map: BTreeMap<char, Vec<usize>>)
+    -> HashMap<char, HashSet<usize>>
+{
+    map.into_iter()
+        .map(|(ch, positions)| {
+            (ch, positions.into_iter().map(|pos| pos - 1).collect())
+        })
+        .collect()
+}
+
+fn describe_chars(chars: &[char]) -> String {
+    if chars.is_empty() {
+        return String::from("none");
+    }
+
+    chars.iter()
+        .map(|ch| ch.to_uppercase().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn buckets(guess: &str, candidates: &[String]) -> HashMap<Feedback, usize> {
+    let mut buckets: HashMap<Feedback, usize> = HashMap::new();
+    for answer in candidates {
+        *buckets.entry(Feedback::compute(guess, answer)).or_insert(0) += 1;
+    }
+    buckets
+}
+
+fn entropy(guess: &str, candidates: &[String]) -> f64 {
+    let buckets = buckets(guess, candidates);
+    let total = candidates.len() as f64;
+    buckets.into_values()
+        .map(|count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+pub(crate) fn worst_case(guess: &str, candidates: &[String]) -> usize {
+    buckets(guess, candidates).into_values().max().unwrap_or(0)
+}
+
+// Each later guess is assumed to halve what is left of its bucket, which
+// makes this an estimate rather than the cost of an optimal strategy.
+fn expected_guesses(guess: &str, candidates: &[String]) -> f64 {
+    if candidates.is_empty() {
+        return 0.0;
+    }
+
+    let total = candidates.len() as f64;
+    let later = buckets(guess, candidates).into_iter()
+        .filter(|(feedback, _)| !feedback.is_solved())
+        .map(|(_, count)| {
+            let count = count as f64;
+            count / total * (1.0 + count.log2())
+        })
+        .sum::<f64>();
+    1.0 + later
+}
+
+fn expected_remaining(guess: &str, candidates: &[String]) -> f64 {
+    if candidates.is_empty() {
+        return 0.0;
+    }
+    let left = buckets(guess, candidates).into_values()
+        .map(|count| count * count)
+        .sum::<usize>();
+    left as f64 / candidates.len() as f64
+}
+
+// Guessing `guess` against each answer leaves the answers sharing its
+// feedback, so the average left is the sum of squared bucket sizes over n.
+fn eliminated(guess: &str, candidates: &[String]) -> usize {
+    let total = candidates.len();
+    if total == 0 {
+        return 0;
+    }
+    let left = buckets(guess, candidates).into_values()
+        .map(|count| count * count)
+        .sum::<usize>();
+    total - (left + total / 2) / total
+}
+
+fn collect_alphabet(words: &[String]) -> HashSet<char> {
+    words.iter().flat_map(|word| word.chars()).collect()
+}
+
+fn letter_frequencies(words: &[String]) -> HashMap<char, usize> {
+    let mut freqs = HashMap::new();
+    for word in words {
+        let chars: HashSet<char> = word.chars().collect();
+        for ch in chars {
+            *freqs.entry(ch).or_insert(0) += 1;
+        }
+    }
+    freqs
+}
+
+fn is_neighbour(a: &str, b: &str) -> bool {
+    if a.chars().count() != b.chars().count() {
+        return false;
+    }
+    a.chars().zip(b.chars()).filter(|(x, y)| x != y).count() == 1
+}
+
+fn position_frequencies(words: &[String], length: usize)
+    -> Vec<HashMap<char, usize>>
+{
+    let mut freqs = vec![HashMap::new(); length];
+    for word in words {
+        for (i, ch) in word.chars().enumerate().take(length) {
+            *freqs[i].entry(ch).or_insert(0) += 1;
+        }
+    }
+    freqs
+}
+
+fn score_word(word: &str, freqs: &HashMap<char, usize>) -> usize {
+    let chars: HashSet<char> = word.chars().collect();
+    chars.iter().map(|ch| freqs.get(ch).unwrap_or(&0)).sum()
+}
+
+fn sort_by_score<F>(words: &mut [String], score: F)
+    where F: Fn(&str) -> usize
+{
+    words.sort_by_cached_key(|word| {
+        (Reverse(score(word)), word.clone())
+    });
+}
+
+fn read_blacklist(blacklist: Option<&Path>) -> io::Result<HashSet<String>> {
+    let mut words = HashSet::new();
+    let path = match blacklist {
+        Some(path) => path,
+        None => return Ok(words)
+    };
+
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            return Ok(words);
+        },
+        Err(error) => return Err(error)
+    };
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let word = line.trim();
+        if !word.is_empty() {
+            words.insert(word.to_lowercase());
+        }
+    }
+
+    Ok(words)
+}
+
+fn read_words(database: &Path, punctuation: Punctuation,
+              blacklist: &HashSet<String>, filter: Option<&WordFilter>,
+              max_line: usize, warnings: Option<&mut Vec<Warning>>)
+    -> io::Result<Vec<String>>
+{
+    if database.extension().is_some_and(|ext| ext == "gz") {
+        return read_compressed(database, punctuation, blacklist, filter,
+                               max_line, warnings);
+    }
+
+    let file = File::open(database)?;
+    let size = file.metadata()?.len() as usize;
+    parse_words(BufReader::new(file), size, punctuation, blacklist, filter,
+                max_line, warnings)
+}
+
+#[cfg(feature = "gzip")]
+fn read_compressed(database: &Path, punctuation: Punctuation,
+                   blacklist: &HashSet<String>, filter: Option<&WordFilter>,
+                   max_line: usize, warnings: Option<&mut Vec<Warning>>)
+    -> io::Result<Vec<String>>
+{
+    // The compressed size is only a lower bound on the buffers needed.
+    let file = File::open(database)?;
+    let size = file.metadata()?.len() as usize;
+    let decoder = MultiGzDecoder::new(BufReader::new(file));
+    parse_words(BufReader::new(decoder), size, punctuation, blacklist, filter,
+                max_line, warnings)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn read_compressed(_database: &Path, _punctuation: Punctuation,
+                   _blacklist: &HashSet<String>,
+                   _filter: Option<&WordFilter>, _max_line: usize,
+                   _warnings: Option<&mut Vec<Warning>>)
+    -> io::Result<Vec<String>>
+{
+    Err(io::Error::new(io::ErrorKind::Unsupported,
+                       "compressed word lists require the gzip feature"))
+}
+
+// Reads at most `max_line` bytes of a line, not counting its line ending.
+fn read_line<R: BufRead>(reader: &mut R, max_line: usize, number: usize)
+    -> io::Result<Option<Vec<u8>>>
+{
+    let mut buffer = Vec::new();
+    let read = reader.by_ref()
+        .take(max_line as u64 + 2)
+        .read_until(b'\n', &mut buffer)?;
+    if read == 0 {
+        return Ok(None);
+    }
+
+    if buffer.last() == Some(&b'\n') {
+        buffer.pop();
+        if buffer.last() == Some(&b'\r') {
+            buffer.pop();
+        }
+    }
+    if buffer.len() > max_line {
+        let msg = format!("line {} is longer than {} bytes", number,
+                          max_line);
+        return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+    }
+
+    Ok(Some(buffer))
+}
+
+// Without `warnings` the word list is read strictly, failing on invalid
+// UTF-8. With it, bad lines are decoded lossily, duplicates are dropped and
+// each is reported instead.
+fn parse_words<R: BufRead>(mut reader: R, size: usize,
+                           punctuation: Punctuation,
+                           blacklist: &HashSet<String>,
+                           filter: Option<&WordFilter>, max_line: usize,
+                           mut warnings: Option<&mut Vec<Warning>>)
+    -> io::Result<Vec<String>>
+{
+    let capacity = size / AVERAGE_LINE;
+    let mut matches = Vec::with_capacity(capacity);
+    let mut seen = HashSet::with_capacity(capacity);
+    let mut number = 0;
+
+    while let Some(bytes) = read_line(&mut reader, max_line, number + 1)? {
+        number += 1;
+        let line = match (String::from_utf8(bytes), warnings.as_deref_mut()) {
+            (Ok(line), _) => line,
+            (Err(error), Some(warnings)) => {
+                warnings.push(Warning::InvalidEncoding { line: number });
+                String::from_utf8_lossy(error.as_bytes()).into_owned()
+            },
+            (Err(_), None) => {
+                let msg = format!("line {} is not valid UTF-8", number);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+        };
+
+        let word = match punctuation {
+            Punctuation::Skip => {
+                match line.chars().all(char::is_alphabetic) {
+                    true => line.clone(),
+                    false => String::new()
+                }
+            },
+
+            Punctuation::Strip => {
+                line.chars().filter(|ch| ch.is_alphabetic()).collect()
+            }
+        };
+
+        let word = word.to_lowercase();
+        if word.is_empty() {
+            if let Some(warnings) = warnings.as_deref_mut() {
+                if !line.trim().is_empty() {
+                    warnings.push(Warning::SkippedLine {
+                        line: number,
+                        text: line
+                    });
+                }
+            }
+            continue;
+        }
+        if blacklist.contains(&word) {
+            continue;
+        }
+        if filter.is_some_and(|filter| !filter(&word)) {
+            continue;
+        }
+
+        if !seen.insert(word.clone()) {
+            if let Some(warnings) = warnings.as_deref_mut() {
+                warnings.push(Warning::Duplicate { line: number, word });
+            }
+            continue;
+        }
+        matches.push(word);
+    }
+
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(words: &[&str]) -> Dictionary {
+        let words = words.iter().map(|word| word.to_string())
+            .collect::<Vec<_>>();
+        Dictionary::from_words(&words, 5)
+    }
+
+    #[test]
+    fn keep_greens_pinning_an_excluded_letter() {
+        let words = ["abcde", "bacde", "xyzwv"];
+        let dictionary = fixture(&words);
+        dictionary.set_keep_greens(true);
+        dictionary.add_char(SetType::Excluded, 'a');
+        assert_eq!(dictionary.remaining().to_vec(), ["xyzwv"]);
+        dictionary.set_char_position(1, 'a');
+
+        let fresh = fixture(&words);
+        fresh.set_keep_greens(true);
+        fresh.add_char(SetType::Excluded, 'a');
+        fresh.set_char_position(1, 'a');
+        assert_eq!(dictionary.remaining().to_vec(), ["abcde"]);
+        assert_eq!(*dictionary.remaining(), *fresh.remaining());
+    }
+
+    const WORDS: [&str; 5] = ["crane", "slate", "trace", "zebra", "abcde"];
+
+    fn assert_same_matches(dictionary: &Dictionary, fresh: &Dictionary) {
+        assert_eq!(*dictionary.matches(), *fresh.matches());
+    }
+
+    #[test]
+    fn remove_char_relaxes_cached_matches() {
+        let dictionary = fixture(&WORDS);
+        dictionary.add_char(SetType::Excluded, 'e');
+        dictionary.add_char(SetType::Included, 'z');
+        assert!(dictionary.remaining().is_empty());
+        dictionary.remove_char(SetType::Excluded, 'e');
+        assert_same_matches(&dictionary, &{
+            let fresh = fixture(&WORDS);
+            fresh.add_char(SetType::Included, 'z');
+            fresh
+        });
+        dictionary.remove_char(SetType::Included, 'z');
+        assert_same_matches(&dictionary, &fixture(&WORDS));
+    }
+
+    #[test]
+    fn clear_set_relaxes_cached_matches() {
+        let dictionary = fixture(&WORDS);
+        dictionary.add_char(SetType::Excluded, 'a');
+        dictionary.add_char(SetType::Excluded, 'e');
+        assert!(dictionary.remaining().is_empty());
+        dictionary.clear_set(SetType::Excluded);
+        assert_same_matches(&dictionary, &fixture(&WORDS));
+    }
+
+    #[test]
+    fn set_char_position_relaxes_cached_matches() {
+        let dictionary = fixture(&WORDS);
+        dictionary.set_char_position(1, 'c');
+        assert_eq!(dictionary.remaining().to_vec(), ["crane"]);
+        dictionary.set_char_position(1, 's');
+        assert_same_matches(&dictionary, &{
+            let fresh = fixture(&WORDS);
+            fresh.set_char_position(1, 's');
+            fresh
+        });
+        dictionary.unset_char_position(1);
+        assert_same_matches(&dictionary, &fixture(&WORDS));
+    }
+
+    #[test]
+    fn remove_present_char_relaxes_cached_matches() {
+        let dictionary = fixture(&WORDS);
+        dictionary.add_present_char('r', 2);
+        assert_eq!(dictionary.remaining().to_vec(), ["zebra"]);
+        dictionary.remove_present_char('r', 2);
+        assert_same_matches(&dictionary, &fixture(&WORDS));
+    }
+
+    #[test]
+    fn clear_set_restores_all_candidates() {
+        for set_type in [SetType::Included, SetType::Excluded] {
+            let dictionary = fixture(&WORDS);
+            let ch = match set_type {
+                SetType::Included => 'z',
+                SetType::Excluded => 'a'
+            };
+            dictionary.add_char(set_type, ch);
+            assert!(dictionary.remaining().len() < WORDS.len());
+            dictionary.clear_set(set_type);
+            assert_eq!(dictionary.remaining().len(), WORDS.len());
+        }
+    }
+
+    #[test]
+    fn present_letters_are_kept_apart_from_includes() {
+        let dictionary = fixture(&WORDS);
+        dictionary.add_char(SetType::Included, 'r');
+        dictionary.add_present_char('R', 2);
+        dictionary.add_present_char('r', 3);
+        assert_eq!(dictionary.present_positions('r'), [2, 3]);
+        assert_eq!(dictionary.remaining().to_vec(), ["zebra"]);
+
+        dictionary.remove_present_char('r', 2);
+        dictionary.remove_present_char('r', 3);
+        assert!(dictionary.present_positions('r').is_empty());
+        assert_eq!(dictionary.included_chars(), ['r']);
+        assert_same_matches(&dictionary, &{
+            let fresh = fixture(&WORDS);
+            fresh.add_char(SetType::Included, 'r');
+            fresh
+        });
+    }
+
+    #[test]
+    fn present_letters_are_required() {
+        let dictionary = fixture(&WORDS);
+        dictionary.add_present_char('z', 2);
+        assert_eq!(dictionary.remaining().to_vec(), ["zebra"]);
+        assert!(dictionary.included_chars().is_empty());
+
+        dictionary.reset();
+        assert!(dictionary.present_positions('z').is_empty());
+        assert_same_matches(&dictionary, &fixture(&WORDS));
+    }
+
+    #[test]
+    fn restore_puts_back_every_constraint() {
+        let words = ["crane", "slate", "trace", "zebra"];
+        let dictionary = fixture(&words);
+        dictionary.set_allowed_at(5, &['a', 'e']);
+        dictionary.set_letter_parity('q', true);
+        dictionary.add_present_char('e', 1);
+        let snapshot = dictionary.snapshot();
+        let before = dictionary.remaining().to_vec();
+        assert_eq!(before.len(), 4);
+
+        let feedback = Feedback::parse("XYXXX").unwrap();
+        dictionary.apply_feedback("crane", &feedback).unwrap();
+        dictionary.set_min_count('t', 2);
+        dictionary.forbid_adjacent('s', 'l');
+        dictionary.set_letter_multiset("zebra");
+        assert!(dictionary.remaining().len() < before.len());
+
+        dictionary.restore(snapshot.clone()).unwrap();
+        assert_eq!(dictionary.remaining().to_vec(), before);
+        assert_eq!(dictionary.snapshot(), snapshot);
+        assert!(dictionary.history().is_empty());
+    }
+
+    #[test]
+    fn restore_rejects_a_mismatched_state() {
+        let dictionary = fixture(&WORDS);
+        let mut state = DictionaryState {
+            positions: Some(vec!['.'; 4]),
+            ..DictionaryState::default()
+        };
+        assert!(dictionary.restore(state.clone()).is_err());
+
+        state.positions = None;
+        state.not_at.insert('a', vec![6]);
+        assert!(dictionary.restore(state).is_err());
+    }
+
+    #[test]
+    fn missing_word_list_is_an_error() {
+        let path = std::env::temp_dir().join("girdle-missing-words");
+        assert!(WordList::load(&[&path], Punctuation::Skip).is_err());
+    }
+
+    #[cfg(feature = "embedded-dict")]
+    #[test]
+    fn from_embedded_parses_the_embedded_list() {
+        let dictionary = Dictionary::from_embedded(5).unwrap();
+        let word_list = WordList::embedded(Punctuation::Skip, None).unwrap();
+        assert_eq!(dictionary.word_count(),
+                   Dictionary::from_word_list(&word_list, 5).word_count());
+        assert!(dictionary.remaining().iter()
+                .all(|word| word.chars().all(char::is_alphabetic)));
+    }
+
+    fn parse(text: &str, punctuation: Punctuation,
+             warnings: Option<&mut Vec<Warning>>) -> Vec<String>
+    {
+        parse_words(text.as_bytes(), text.len(), punctuation,
+                    &HashSet::new(), None, MAX_LINE_LENGTH, warnings)
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_words_always_removes_duplicates() {
+        let text = "Crane\ncrane\nslate\nCRANE\n";
+        assert_eq!(parse(text, Punctuation::Skip, None), ["crane", "slate"]);
+
+        let mut warnings = Vec::new();
+        let words = parse(text, Punctuation::Skip, Some(&mut warnings));
+        assert_eq!(words, ["crane", "slate"]);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter()
+                .all(|warning| matches!(warning, Warning::Duplicate { .. })));
+    }
+
+    #[test]
+    fn suggest_counts_repeated_letters_once() {
+        let dictionary = fixture(&["eeeee", "abcde", "abfgh", "eijkl"]);
+        assert_eq!(dictionary.suggest().as_deref(), Some("abcde"));
+    }
+
+    #[test]
+    fn suggest_breaks_ties_alphabetically() {
+        let dictionary = fixture(&["edcba", "cabde", "abcde"]);
+        assert_eq!(dictionary.suggest().as_deref(), Some("abcde"));
+        assert_eq!(fixture(&[]).suggest(), None);
+    }
+
+    #[test]
+    fn positions_count_characters_rather_than_bytes() {
+        let words = ["café", "cafe", "éclat", "cafés"]
+            .iter().map(|word| word.to_string()).collect::<Vec<_>>();
+        let dictionary = Dictionary::from_words(&words, 4);
+        assert_eq!(dictionary.word_count(), 2);
+
+        dictionary.set_char_position(4, 'é');
+        assert_eq!(dictionary.char_position(4), Some('é'));
+        assert_eq!(dictionary.remaining().to_vec(), ["café"]);
+
+        let dictionary = Dictionary::from_words(&words, 5);
+        dictionary.set_char_position(4, 'é');
+        dictionary.set_char_position(5, 's');
+        assert_eq!(dictionary.remaining().to_vec(), ["cafés"]);
+    }
+
+    #[test]
+    fn annotated_matches_without_remaining_answers() {
+        let dictionary = fixture(&WORDS);
+        assert_eq!(dictionary.annotated_matches().len(), WORDS.len());
+
+        dictionary.set_answers(&["zebra".to_string()]);
+        let annotated = dictionary.annotated_matches();
+        assert_eq!(annotated.len(), WORDS.len());
+        assert_eq!(annotated.iter()
+                   .filter(|info| info.likelihood > 0.0)
+                   .map(|info| info.word.as_str())
+                   .collect::<Vec<_>>(), ["zebra"]);
+
+        dictionary.add_char(SetType::Excluded, 'z');
+        assert!(!dictionary.remaining().is_empty());
+        assert!(dictionary.annotated_matches().is_empty());
+    }
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join(format!("girdle-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    const PUNCTUATED: &str = "don't\nne'er\nself-made\ncrane\nslate\n";
+
+    #[test]
+    fn punctuation_skip_drops_punctuated_words() {
+        let path = temp_file("skip", PUNCTUATED);
+        let word_list = WordList::load(&[&path], Punctuation::Skip).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(word_list.words(), ["crane", "slate"]);
+    }
+
+    #[test]
+    fn punctuation_strip_keeps_the_letters() {
+        let path = temp_file("strip", PUNCTUATED);
+        let word_list = WordList::load(&[&path], Punctuation::Strip).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(word_list.words(),
+                   ["dont", "neer", "selfmade", "crane", "slate"]);
+
+        let dictionary = Dictionary::from_word_list(&word_list, 4);
+        assert_eq!(dictionary.remaining().to_vec(), ["dont", "neer"]);
+    }
+
+    #[test]
+    fn exclude_letter_at_only_rejects_the_listed_positions() {
+        let dictionary = fixture(&WORDS);
+        dictionary.exclude_letter_at('T', &[1, 5]);
+        assert_eq!(dictionary.remaining().to_vec(),
+                   ["crane", "slate", "zebra", "abcde"]);
+
+        dictionary.exclude_letter_at('e', &[5]);
+        assert_eq!(dictionary.remaining().to_vec(), ["zebra"]);
+        assert!(dictionary.tried_chars().contains(&'t'));
+    }
+
+    #[test]
+    #[should_panic]
+    fn exclude_letter_at_rejects_bad_positions() {
+        fixture(&WORDS).exclude_letter_at('t', &[0]);
+    }
+
+    #[test]
+    fn position_letter_counts_over_remaining_words() {
+        let dictionary = fixture(&WORDS);
+        assert_eq!(dictionary.position_letter_counts(5), [('e', 4), ('a', 1)]);
+        assert_eq!(dictionary.position_letter_counts(1),
+                   [('a', 1), ('c', 1), ('s', 1), ('t', 1), ('z', 1)]);
+
+        dictionary.add_char(SetType::Excluded, 'z');
+        assert_eq!(dictionary.position_letter_counts(5), [('e', 4)]);
+        assert_eq!(dictionary.possible_letters_at(2), ['b', 'l', 'r']);
+    }
+
+    #[test]
+    fn try_set_char_position_refuses_to_overwrite_a_green() {
+        let dictionary = fixture(&WORDS);
+        dictionary.try_set_char_position(1, 'c').unwrap();
+        dictionary.try_set_char_position(1, 'c').unwrap();
+        assert!(dictionary.try_set_char_position(1, 's').is_err());
+        assert_eq!(dictionary.char_position(1), Some('c'));
+
+        dictionary.try_set_char_position(1, '.').unwrap();
+        dictionary.try_set_char_position(1, 's').unwrap();
+        assert_eq!(dictionary.remaining().to_vec(), ["slate"]);
+    }
+
+    #[test]
+    fn feedback_refuses_to_overwrite_a_green() {
+        let dictionary = fixture(&WORDS);
+        dictionary.set_char_position(1, 'c');
+        let feedback = Feedback::parse("GXXXX").unwrap();
+        assert!(dictionary.apply_feedback("slate", &feedback).is_err());
+        assert!(dictionary.history().is_empty());
+        assert!(dictionary.excluded_chars().is_empty());
+        assert!(dictionary.apply_partial_feedback(1, 's',
+                                                  LetterState::Correct)
+                .is_err());
+        assert_eq!(dictionary.char_position(1), Some('c'));
+    }
+
+    fn assert_contradiction(dictionary: &Dictionary, reason: &str) {
+        assert!(!dictionary.is_satisfiable());
+        let contradiction = dictionary.contradiction().unwrap();
+        assert!(contradiction.contains(reason), "{}", contradiction);
+    }
+
+    #[test]
+    fn no_contradiction_without_constraints() {
+        let dictionary = fixture(&WORDS);
+        assert!(dictionary.is_satisfiable());
+        dictionary.add_char(SetType::Excluded, 'x');
+        dictionary.add_char(SetType::Included, 'a');
+        dictionary.set_char_position(1, 'c');
+        assert!(dictionary.is_satisfiable());
+    }
+
+    #[test]
+    fn contradiction_multiset_length() {
+        let dictionary = fixture(&WORDS);
+        dictionary.set_letter_multiset("abc");
+        assert_contradiction(&dictionary, "multiset has 3 letters");
+    }
+
+    #[test]
+    fn contradiction_position_allows_only_excluded() {
+        let dictionary = fixture(&WORDS);
+        dictionary.add_char(SetType::Excluded, 'a');
+        dictionary.add_char(SetType::Excluded, 'b');
+        dictionary.set_allowed_at(2, &['a', 'b']);
+        assert_contradiction(&dictionary, "position 2 only allows excluded");
+    }
+
+    #[test]
+    fn contradiction_excluded_green() {
+        let dictionary = fixture(&WORDS);
+        dictionary.set_char_position(3, 'a');
+        dictionary.add_char(SetType::Excluded, 'a');
+        assert_contradiction(&dictionary, "'a' is excluded but is correct");
+
+        dictionary.set_keep_greens(true);
+        assert!(dictionary.is_satisfiable());
+    }
+
+    #[test]
+    fn contradiction_odd_parity_excluded() {
+        let dictionary = fixture(&WORDS);
+        dictionary.add_char(SetType::Excluded, 'q');
+        dictionary.set_letter_parity('q', false);
+        assert_contradiction(&dictionary, "odd number of times");
+    }
+
+    #[test]
+    fn contradiction_required_and_excluded() {
+        let dictionary = fixture(&WORDS);
+        dictionary.add_char(SetType::Excluded, 'a');
+        dictionary.set_min_count('a', 2);
+        assert_contradiction(&dictionary, "'a' is both required and excluded");
+    }
+
+    #[test]
+    fn contradiction_no_position_left() {
+        let dictionary = fixture(&WORDS);
+        for pos in 1..=5 {
+            dictionary.add_present_char('z', pos);
+        }
+        assert_contradiction(&dictionary, "'z' is required but has no \
+                                           position left");
+    }
+
+    #[test]
+    fn contradiction_too_many_repeats() {
+        let dictionary = fixture(&WORDS);
+        for (i, ch) in "abcd".chars().enumerate() {
+            dictionary.set_char_position(i+1, ch);
+        }
+        dictionary.set_min_count('e', 2);
+        assert_contradiction(&dictionary, "'e' must appear 2 times but fits \
+                                           in at most 1");
+    }
+
+    #[test]
+    fn contradiction_too_many_required_letters() {
+        let dictionary = fixture(&WORDS);
+        for (i, ch) in "abc".chars().enumerate() {
+            dictionary.set_char_position(i+1, ch);
+        }
+        for ch in "xyz".chars() {
+            dictionary.add_char(SetType::Included, ch);
+        }
+        assert_contradiction(&dictionary, "3 required letters do not fit in \
+                                           2 open positions");
+    }
+
+    #[test]
+    fn forbid_adjacent_rejects_either_order() {
+        let dictionary = fixture(&WORDS);
+        dictionary.forbid_adjacent('R', 'a');
+        assert_eq!(dictionary.remaining().to_vec(), ["slate", "abcde"]);
+        assert_eq!(dictionary.forbidden_adjacent(), [('a', 'r')]);
+
+        dictionary.forbid_adjacent('a', 'r');
+        assert_eq!(dictionary.forbidden_adjacent(), [('a', 'r')]);
+        dictionary.forbid_adjacent('a', 'b');
+        assert_eq!(dictionary.remaining().to_vec(), ["slate"]);
+    }
+
+    #[test]
+    fn forbid_adjacent_ignores_letters_apart() {
+        let dictionary = fixture(&WORDS);
+        dictionary.forbid_adjacent('c', 'e');
+        assert_eq!(dictionary.remaining().to_vec(),
+                   ["crane", "slate", "zebra", "abcde"]);
+
+        dictionary.clear_adjacent();
+        assert!(dictionary.forbidden_adjacent().is_empty());
+        assert_same_matches(&dictionary, &fixture(&WORDS));
+    }
+
+    #[test]
+    fn lines_longer_than_the_maximum_are_an_error() {
+        let contents = format!("crane\r\n{}\nslate\n", "a".repeat(20));
+        let path = temp_file("long-line", &contents);
+        let word_list = WordList::with_max_line(&[&path], Punctuation::Skip,
+                                                20).unwrap();
+        assert_eq!(word_list.len(), 3);
+
+        let error = WordList::with_max_line(&[&path], Punctuation::Skip, 19)
+            .err().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(error.to_string().contains("line 2 is longer than 19 bytes"),
+                "{}", error);
+    }
+
+    #[test]
+    fn export_markdown_golden() {
+        let dictionary = fixture(&WORDS);
+        assert_eq!(dictionary.export_markdown(), "");
+
+        let guesses = [("slate", "XXYXG"), ("crane", "GGGGG")];
+        for (guess, feedback) in guesses {
+            let feedback = Feedback::parse(feedback).unwrap();
+            dictionary.apply_feedback(guess, &feedback).unwrap();
+        }
+        assert_eq!(dictionary.export_markdown(), "\
+| # | 1 | 2 | 3 | 4 | 5 |
+|---|---|---|---|---|---|
+| 1 | S \u{2B1B} | L \u{2B1B} | A \u{1F7E8} | T \u{2B1B} | E \u{1F7E9} |
+| 2 | C \u{1F7E9} | R \u{1F7E9} | A \u{1F7E9} | N \u{1F7E9} | E \u{1F7E9} |");
+    }
+
+    const THEMED: &str = "adieu\naudio\ncrane\neerie\nllama\nocean\n";
+
+    #[test]
+    fn new_matching_without_repeated_letters() {
+        let path = temp_file("no-repeats", THEMED);
+        let dictionary = Dictionary::new_matching(&[&path], 5, |word| {
+            let letters: HashSet<char> = word.chars().collect();
+            letters.len() == word.chars().count()
+        }).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(dictionary.remaining().to_vec(),
+                   ["adieu", "audio", "crane", "ocean"]);
+    }
+
+    #[test]
+    fn new_matching_starting_with_a_vowel() {
+        let path = temp_file("vowels", THEMED);
+        let dictionary = Dictionary::new_matching(&[&path], 5, |word| {
+            word.starts_with(|ch| "aeiou".contains(ch))
+        }).unwrap();
+        assert_eq!(dictionary.word_count(), 4);
+        assert_eq!(dictionary.remaining().to_vec(),
+                   ["adieu", "audio", "eerie", "ocean"]);
+
+        // The filter is kept for reloading.
+        fs::write(&path, "abbey\nbread\n").unwrap();
+        dictionary.reload().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(dictionary.remaining().to_vec(), ["abbey"]);
+    }
+
+    #[test]
+    fn partial_feedback_green() {
+        let dictionary = fixture(&WORDS);
+        dictionary.apply_partial_feedback(1, 'C', LetterState::Correct)
+            .unwrap();
+        assert_eq!(dictionary.char_position(1), Some('c'));
+        assert_eq!(dictionary.remaining().to_vec(), ["crane"]);
+        assert!(dictionary.history().is_empty());
+    }
+
+    #[test]
+    fn partial_feedback_yellow() {
+        let dictionary = fixture(&WORDS);
+        dictionary.apply_partial_feedback(2, 'r', LetterState::Present)
+            .unwrap();
+        assert_eq!(dictionary.included_chars(), ['r']);
+        assert_eq!(dictionary.remaining().to_vec(), ["zebra"]);
+    }
+
+    #[test]
+    fn partial_feedback_gray() {
+        let dictionary = fixture(&WORDS);
+        dictionary.apply_partial_feedback(1, 'z', LetterState::Absent)
+            .unwrap();
+        assert_eq!(dictionary.excluded_chars(), ['z']);
+        assert_eq!(dictionary.remaining().len(), 4);
+
+        // A gray for a letter known to be in the word only rules out the
+        // position.
+        dictionary.apply_partial_feedback(3, 'a', LetterState::Present)
+            .unwrap();
+        dictionary.apply_partial_feedback(4, 'a', LetterState::Absent)
+            .unwrap();
+        assert!(!dictionary.excluded_chars().contains(&'a'));
+        assert_eq!(dictionary.remaining().to_vec(), ["abcde"]);
+    }
+
+    #[test]
+    fn partial_feedback_refuses_to_overwrite_a_green() {
+        let dictionary = fixture(&WORDS);
+        dictionary.apply_partial_feedback(1, 'c', LetterState::Correct)
+            .unwrap();
+        dictionary.apply_partial_feedback(1, 'c', LetterState::Correct)
+            .unwrap();
+        assert!(dictionary.apply_partial_feedback(1, 's',
+                                                  LetterState::Correct)
+                .is_err());
+        assert_eq!(dictionary.char_position(1), Some('c'));
+        assert_eq!(dictionary.remaining().to_vec(), ["crane"]);
+    }
+
+    #[test]
+    fn partial_feedback_out_of_range() {
+        let dictionary = fixture(&WORDS);
+        for pos in [0, 6] {
+            assert!(dictionary.apply_partial_feedback(pos, 'a',
+                                                      LetterState::Absent)
+                    .is_err());
+        }
+        assert!(dictionary.tried_chars().is_empty());
+        assert_eq!(dictionary.remaining().len(), WORDS.len());
+    }
+
+    // Every guess splits the ?at candidates into a bucket of one and a
+    // bucket of two, so the choice is down to the tie-break.
+    fn tied_guesses() -> Dictionary {
+        let words = ["abz", "bat", "cat", "hat"]
+            .iter().map(|word| word.to_string()).collect::<Vec<_>>();
+        let dictionary = Dictionary::from_words(&words, 3);
+        dictionary.set_char_position(2, 'a');
+        dictionary.set_char_position(3, 't');
+        dictionary
+    }
+
+    #[test]
+    fn tie_break_prefers_candidates() {
+        let dictionary = tied_guesses();
+        assert_eq!(dictionary.remaining().to_vec(), ["bat", "cat", "hat"]);
+        assert!((dictionary.expected_information("abz") -
+                 dictionary.expected_information("bat")).abs() < TIE_EPSILON);
+        assert_eq!(dictionary.suggest_guess_with(TieBreak::Candidates)
+                   .as_deref(), Some("bat"));
+        assert_eq!(dictionary.suggest_guess().as_deref(), Some("bat"));
+    }
+
+    #[test]
+    fn tie_break_alphabetical() {
+        let dictionary = tied_guesses();
+        assert_eq!(dictionary.suggest_guess_with(TieBreak::Alphabetical)
+                   .as_deref(), Some("abz"));
+    }
+
+    #[test]
+    fn preview_positions_shrinks_without_committing() {
+        let dictionary = fixture(&["crane", "crate", "cream", "chart",
+                                   "cared", "slate"]);
+        dictionary.remaining();
+        let cached = dictionary.matches.borrow().clone();
+
+        assert_eq!(dictionary.preview_positions("c"),
+                   ["crane", "crate", "cream", "chart", "cared"]);
+        assert_eq!(dictionary.preview_positions("Cr"),
+                   ["crane", "crate", "cream"]);
+        assert_eq!(dictionary.preview_positions("cra"), ["crane", "crate"]);
+        assert_eq!(dictionary.preview_positions("c.e"), ["cream"]);
+
+        assert_eq!(*dictionary.matches.borrow(), cached);
+        assert_eq!(dictionary.state(), DictionaryState::default());
+        assert_eq!(dictionary.remaining().len(), 6);
+    }
+
+    #[test]
+    fn reload_keeps_constraints_and_history() {
+        let path = temp_file("keep", "crane\nslate\nstale\n");
+        let dictionary = Dictionary::new(&[&path], 5).unwrap();
+        let feedback = Feedback::parse("XXGXG").unwrap();
+        dictionary.apply_feedback("crane", &feedback).unwrap();
+        let state = dictionary.state();
+        assert_eq!(dictionary.remaining().to_vec(), ["slate", "stale"]);
+
+        fs::write(&path, "crane\nslate\nstale\nshade\nshame\n").unwrap();
+        dictionary.reload().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(dictionary.word_count(), 5);
+        assert_eq!(dictionary.state(), state);
+        assert_eq!(dictionary.history().len(), 1);
+        assert_eq!(dictionary.remaining().to_vec(),
+                   ["slate", "stale", "shade", "shame"]);
+    }
+
+    #[test]
+    fn word_list_reload_rereads_the_file() {
+        let path = temp_file("list", "crane\nslate\n");
+        let word_list = WordList::load(&[&path], Punctuation::Skip).unwrap();
+        fs::write(&path, "crane\nslate\nstare\n").unwrap();
+        let reloaded = word_list.reload().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(word_list.len(), 2);
+        assert_eq!(reloaded.words(), ["crane", "slate", "stare"]);
+        assert_eq!(reloaded.path(), word_list.path());
+    }
+
+    #[test]
+    fn failed_reload_keeps_everything() {
+        let path = temp_file("gone", "crane\ncrate\nslate\n");
+        let dictionary = Dictionary::new(&[&path], 5).unwrap();
+        dictionary.set_char_position(1, 'c');
+        let state = dictionary.state();
+        let words = dictionary.words.borrow().clone();
+        assert_eq!(dictionary.remaining().to_vec(), ["crane", "crate"]);
+
+        fs::remove_file(&path).unwrap();
+        assert!(dictionary.reload().is_err());
+        assert_eq!(*dictionary.words.borrow(), words);
+        assert_eq!(dictionary.word_count(), 3);
+        assert_eq!(dictionary.state(), state);
+        assert_eq!(dictionary.remaining().to_vec(), ["crane", "crate"]);
+    }
+
+    #[test]
+    fn minimax_splits_the_candidates() {
+        let words = ["bat", "cat", "chm", "hat", "mat"]
+            .iter().map(|word| word.to_string()).collect::<Vec<_>>();
+        let dictionary = Dictionary::from_words(&words, 3);
+        dictionary.set_char_position(2, 'a');
+        dictionary.set_char_position(3, 't');
+        assert_eq!(dictionary.remaining().to_vec(),
+                   ["bat", "cat", "hat", "mat"]);
+
+        assert_eq!(dictionary.worst_case("bat"), 3);
+        assert_eq!(dictionary.worst_case("CHM"), 1);
+        assert_eq!(dictionary.suggest_minimax().as_deref(), Some("chm"));
+    }
+
+    #[test]
+    fn letter_parity_filters_by_count() {
+        let dictionary = fixture(&["apple", "spelt", "crane", "puppy"]);
+        dictionary.set_letter_parity('P', true);
+        assert_eq!(dictionary.remaining().to_vec(), ["apple", "crane"]);
+
+        dictionary.set_letter_parity('p', false);
+        assert_eq!(dictionary.remaining().to_vec(), ["spelt", "puppy"]);
+
+        dictionary.clear_letter_parity('p');
+        assert_eq!(dictionary.remaining().len(), 4);
+    }
+
+    #[test]
+    fn letter_multiset_keeps_exact_anagrams() {
+        let dictionary = fixture(&["slate", "sleet", "stale", "stall",
+                                   "steal", "crane"]);
+        dictionary.set_letter_multiset("LEAST");
+        assert_eq!(dictionary.letter_multiset().as_deref(), Some("aelst"));
+        assert_eq!(dictionary.remaining().to_vec(),
+                   ["slate", "stale", "steal"]);
+
+        dictionary.clear_letter_multiset();
+        assert_eq!(dictionary.remaining().len(), 6);
+    }
+
+    fn likelihoods(dictionary: &Dictionary) -> Vec<(String, f64)> {
+        let mut likelihoods = dictionary.annotated_matches().into_iter()
+            .map(|info| (info.word, info.likelihood))
+            .collect::<Vec<_>>();
+        likelihoods.sort_by(|a, b| a.0.cmp(&b.0));
+        likelihoods
+    }
+
+    #[test]
+    fn annotated_matches_are_uniform_without_a_source() {
+        let dictionary = fixture(&["crane", "slate", "trace"]);
+        for (_, likelihood) in likelihoods(&dictionary) {
+            assert!((likelihood - 1.0 / 3.0).abs() < 1e-9);
+        }
+
+        let annotated = dictionary.annotated_matches();
+        assert_eq!(annotated.len(), 3);
+        assert!(annotated.iter().all(|info| info.expected_guesses >= 1.0));
+    }
+
+    #[test]
+    fn annotated_matches_use_the_answers_as_a_prior() {
+        let dictionary = fixture(&["crane", "slate", "trace"]);
+        dictionary.set_answers(&[String::from("crane"),
+                                 String::from("slate")]);
+        assert_eq!(likelihoods(&dictionary),
+                   [(String::from("crane"), 0.5),
+                    (String::from("slate"), 0.5),
+                    (String::from("trace"), 0.0)]);
+
+        dictionary.set_char_position(1, 't');
+        assert!(dictionary.annotated_matches().is_empty());
+    }
+
+    #[test]
+    fn annotated_matches_use_the_frequencies() {
+        let path = temp_file("frequencies", "Crane\t3\n\nslate\t1\n");
+        let frequencies = read_frequencies(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let dictionary = fixture(&["crane", "slate", "trace"]);
+        dictionary.set_frequencies(&frequencies);
+        assert!(dictionary.has_frequencies());
+        assert_eq!(likelihoods(&dictionary),
+                   [(String::from("crane"), 0.75),
+                    (String::from("slate"), 0.25),
+                    (String::from("trace"), 0.0)]);
+
+        // Without a count for any answer, they are equally likely again.
+        dictionary.set_char_position(1, 't');
+        assert_eq!(likelihoods(&dictionary), [(String::from("trace"), 1.0)]);
+    }
+
+    #[test]
+    fn read_frequencies_rejects_bad_lines() {
+        let path = temp_file("bad-frequencies", "crane\t3\nslate 1\n");
+        let error = read_frequencies(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(error.to_string().starts_with("Invalid frequency on line 2"));
+    }
+
+    #[test]
+    fn blacklisted_words_are_filtered() {
+        let source = temp_file("blacklisted", "crane\nslate\nzebra\n");
+        let blacklist = temp_file("blacklist", "SLATE\n");
+        let dictionary = Dictionary::with_blacklist(&[&source], 5,
+                                                    &blacklist).unwrap();
+        assert_eq!(dictionary.remaining().to_vec(), ["crane", "zebra"]);
+        assert_eq!(dictionary.word_count(), 2);
+
+        dictionary.blacklist_word("Zebra").unwrap();
+        let contents = fs::read_to_string(&blacklist).unwrap();
+        assert_eq!(dictionary.remaining().to_vec(), ["crane"]);
+        assert_eq!(dictionary.initial_count(), 1);
+        assert!(!dictionary.alphabet.borrow().contains(&'z'));
+
+        // The word stays out when the list is read again.
+        dictionary.reload().unwrap();
+        fs::remove_file(&source).unwrap();
+        fs::remove_file(&blacklist).unwrap();
+        assert_eq!(contents, "SLATE\nzebra\n");
+        assert_eq!(dictionary.remaining().to_vec(), ["crane"]);
+    }
+
+    #[test]
+    fn feedback_of_the_wrong_length_is_rejected() {
+        let dictionary = fixture(&WORDS);
+        let feedback = Feedback::parse("GGGG").unwrap();
+        let error = dictionary.apply_feedback("crane", &feedback).unwrap_err();
+        assert_eq!(error.to_string(),
+                   "Guess and feedback must be 5 characters.");
+        assert!(dictionary.history().is_empty());
+    }
+
+    #[test]
+    fn emoji_grid_has_a_row_per_guess() {
+        let dictionary = fixture(&WORDS);
+        assert_eq!(dictionary.export_emoji_grid(), "");
+
+        for (guess, feedback) in [("slate", "XXGYG"), ("trace", "GGGGG")] {
+            let feedback = Feedback::parse(feedback).unwrap();
+            dictionary.apply_feedback(guess, &feedback).unwrap();
+        }
+        assert_eq!(dictionary.export_emoji_grid(),
+                   "\u{2B1B}\u{2B1B}\u{1F7E9}\u{1F7E8}\u{1F7E9}\n\
+                    \u{1F7E9}\u{1F7E9}\u{1F7E9}\u{1F7E9}\u{1F7E9}");
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn compressed_word_lists_need_the_gzip_feature() {
+        let path = temp_file("words.gz", "");
+        let error = WordList::load(&[&path], Punctuation::Skip).err();
+        fs::remove_file(&path).unwrap();
+        assert!(error.unwrap().to_string().contains("gzip feature"));
+    }
+
+    #[cfg(feature = "gzip")]
+    mod gzip {
+        use super::*;
+
+        const WORDS: &str = "crane\nslate\ntrace\nzebra\ncrate\nstare\nroate\n\
+                             salet\nadieu\naudio\nraise\narise\nirate\nlater\n\
+                             alert\nalter\nreact\ncater\ncaret\ntrade\ntread\n\
+                             rated\n";
+
+        // Written by Python's gzip module with the modification time zeroed.
+        const LEVEL_1: [u8; 100] = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xff, 0x1d,
+            0x8d, 0x31, 0x0a, 0xc0, 0x30, 0x0c, 0x03, 0x77, 0xfd, 0x52, 0xb5,
+            0x35, 0x04, 0x42, 0x0b, 0x8a, 0xbb, 0xf4, 0xf5, 0x8d, 0xb3, 0x08,
+            0x21, 0x8e, 0x53, 0x98, 0xb7, 0xb0, 0x26, 0x4b, 0x28, 0x33, 0x84,
+            0x4f, 0x97, 0x89, 0x70, 0x2f, 0xab, 0x68, 0xc1, 0xcf, 0xe9, 0x9c,
+            0x2a, 0x30, 0x87, 0x5e, 0xf0, 0xcd, 0xf1, 0xc0, 0x1c, 0x4b, 0xa0,
+            0x3b, 0xc7, 0xe1, 0x5b, 0x63, 0x6c, 0xd0, 0x9b, 0x9c, 0xdd, 0x2d,
+            0x46, 0x21, 0xce, 0x1e, 0x5b, 0x56, 0xfd, 0x92, 0xfd, 0x25, 0xe6,
+            0x36, 0x94, 0x12, 0x3f, 0x3a, 0x04, 0x65, 0x85, 0x84, 0x00, 0x00,
+            0x00
+        ];
+
+        const LEVEL_9: [u8; 99] = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x1d,
+            0x8d, 0xc1, 0x0a, 0xc0, 0x30, 0x0c, 0x42, 0xef, 0xfe, 0xa5, 0x4b,
+            0x3c, 0x14, 0xca, 0x06, 0x36, 0xbb, 0xec, 0xeb, 0xd7, 0xf4, 0x22,
+            0x22, 0x4f, 0x0d, 0xf3, 0x16, 0xd6, 0x64, 0x09, 0x65, 0x86, 0xf0,
+            0xe9, 0x32, 0x11, 0xee, 0x64, 0x15, 0x2d, 0xf8, 0x39, 0x9e, 0x53,
+            0x05, 0xe6, 0xd0, 0x0b, 0xbe, 0x39, 0x1e, 0x98, 0x63, 0x09, 0x74,
+            0xeb, 0x38, 0x7c, 0xcf, 0x18, 0x1b, 0xf4, 0x26, 0x67, 0x7b, 0x8b,
+            0x51, 0x88, 0x93, 0xc7, 0x1e, 0xab, 0x7e, 0xc9, 0xfe, 0x12, 0x13,
+            0x5d, 0x4a, 0xfc, 0x3a, 0x04, 0x65, 0x85, 0x84, 0x00, 0x00, 0x00
+        ];
+
+        const MULTI_MEMBER: [u8; 64] = [
+            0x1f, 0x8b, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x66,
+            0x69, 0x72, 0x73, 0x74, 0x00, 0x4b, 0x2e, 0x4a, 0xcc, 0x4b, 0xe5,
+            0x2a, 0xce, 0x49, 0x2c, 0x49, 0xe5, 0x02, 0x00, 0x79, 0x94, 0xe1,
+            0xed, 0x0c, 0x00, 0x00, 0x00, 0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0xff, 0x2b, 0x29, 0x4a, 0x4c, 0x4e, 0xe5, 0x02,
+            0x00, 0x38, 0xce, 0x37, 0x93, 0x06, 0x00, 0x00, 0x00
+        ];
+
+        const EMPTY: [u8; 20] = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x03,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        ];
+
+        fn load(name: &str, data: &[u8]) -> Result<Vec<String>> {
+            let path = std::env::temp_dir()
+                .join(format!("girdle-{}-{}.gz", std::process::id(), name));
+            fs::write(&path, data).unwrap();
+            let word_list = WordList::load(&[&path], Punctuation::Skip);
+            fs::remove_file(&path).unwrap();
+            Ok(word_list?.words().to_vec())
+        }
+
+        fn words(text: &str) -> Vec<String> {
+            text.lines().map(String::from).collect()
+        }
+
+        #[test]
+        fn read_fastest() {
+            assert_eq!(load("fastest", &LEVEL_1).unwrap(), words(WORDS));
+        }
+
+        #[test]
+        fn read_best() {
+            assert_eq!(load("best", &LEVEL_9).unwrap(), words(WORDS));
+        }
+
+        #[test]
+        fn read_multiple_members() {
+            assert_eq!(load("members", &MULTI_MEMBER).unwrap(),
+                       ["crane", "slate", "trace"]);
+        }
+
+        #[test]
+        fn read_empty() {
+            assert!(load("empty", &EMPTY).unwrap().is_empty());
+        }
+
+        #[test]
+        fn read_rejects_a_bad_checksum() {
+            let mut data = LEVEL_9;
+            let crc = data.len() - 8;
+            data[crc] ^= 0xff;
+            assert!(load("checksum", &data).is_err());
+            assert!(load("truncated", &LEVEL_9[..LEVEL_9.len() - 4]).is_err());
+        }
+    }
 }
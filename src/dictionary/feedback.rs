@@ -0,0 +1,172 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::dictionary::Error;
+use crate::dictionary::Result;
+
+#[derive(Clone,Copy,Debug,PartialEq,Eq,Hash)]
+pub enum LetterState
+{
+    Correct,
+    Present,
+    Absent
+}
+
+#[derive(Clone,Debug,PartialEq,Eq,Hash)]
+pub struct Feedback
+{
+    states: Vec<LetterState>
+}
+
+impl LetterState {
+    pub fn from_char(ch: char) -> Option<LetterState> {
+        match ch.to_ascii_uppercase() {
+            'G' => Some(LetterState::Correct),
+            'Y' => Some(LetterState::Present),
+            'X' | 'B' | '.' => Some(LetterState::Absent),
+            _ => None
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            LetterState::Correct => 'G',
+            LetterState::Present => 'Y',
+            LetterState::Absent  => 'X'
+        }
+    }
+
+    pub fn to_emoji(self) -> char {
+        match self {
+            LetterState::Correct => '\u{1F7E9}',
+            LetterState::Present => '\u{1F7E8}',
+            LetterState::Absent  => '\u{2B1B}'
+        }
+    }
+}
+
+impl Feedback {
+    pub fn new(states: Vec<LetterState>) -> Feedback {
+        Feedback { states }
+    }
+
+    pub fn parse(s: &str) -> Result<Feedback> {
+        let mut states = Vec::new();
+        for ch in s.chars() {
+            match LetterState::from_char(ch) {
+                Some(state) => states.push(state),
+                None => {
+                    let msg = format!("Invalid feedback character '{}'.", ch);
+                    return Err(Error::new(&msg));
+                }
+            }
+        }
+        Ok(Feedback::new(states))
+    }
+
+    pub fn compute(guess: &str, answer: &str) -> Feedback {
+        let guess: Vec<char> = guess.chars().collect();
+        let answer: Vec<char> = answer.chars().collect();
+        let mut states = vec![LetterState::Absent; guess.len()];
+        let mut remaining = HashMap::new();
+
+        for (i, ch) in guess.iter().enumerate() {
+            if answer.get(i) == Some(ch) {
+                states[i] = LetterState::Correct;
+            } else if let Some(ch) = answer.get(i) {
+                *remaining.entry(*ch).or_insert(0) += 1;
+            }
+        }
+
+        for (i, ch) in guess.iter().enumerate() {
+            if states[i] == LetterState::Correct { continue; }
+            if let Some(count) = remaining.get_mut(ch) {
+                if *count > 0 {
+                    *count -= 1;
+                    states[i] = LetterState::Present;
+                }
+            }
+        }
+
+        Feedback::new(states)
+    }
+
+    pub fn states(&self) -> &[LetterState] {
+        &self.states
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn to_emoji(&self) -> String {
+        self.states.iter().map(|state| state.to_emoji()).collect()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.states.iter().all(|state| *state == LetterState::Correct)
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for state in &self.states {
+            write!(f, "{}", state.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compute(guess: &str, answer: &str) -> String {
+        Feedback::compute(guess, answer).to_string()
+    }
+
+    #[test]
+    fn compute_marks_greens_and_yellows() {
+        assert_eq!(compute("crane", "crane"), "GGGGG");
+        assert_eq!(compute("trace", "crane"), "XGGYG");
+        assert_eq!(compute("fjord", "crane"), "XXXYX");
+    }
+
+    #[test]
+    fn compute_counts_repeated_letters() {
+        assert_eq!(compute("llama", "hello"), "YYXXX");
+        assert_eq!(compute("hello", "llama"), "XXYYX");
+        assert_eq!(compute("speed", "abide"), "XXYXY");
+        // A green uses up the letter before any yellow is given.
+        assert_eq!(compute("eerie", "crane"), "XXYXG");
+    }
+
+    #[test]
+    fn parse_accepts_either_case_and_gray_aliases() {
+        let feedback = Feedback::parse("gYxB.").unwrap();
+        assert_eq!(feedback.to_string(), "GYXXX");
+        assert_eq!(feedback.len(), 5);
+        assert!(Feedback::parse("GGGGG").unwrap().is_solved());
+    }
+
+    #[test]
+    fn parse_rejects_bad_characters() {
+        let error = Feedback::parse("GYZXX").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid feedback character 'Z'.");
+        assert!(Feedback::parse("GG GG").is_err());
+    }
+
+    #[test]
+    fn to_emoji_uses_squares() {
+        let feedback = Feedback::parse("GYX").unwrap();
+        assert_eq!(feedback.to_emoji(), "\u{1F7E9}\u{1F7E8}\u{2B1B}");
+    }
+}
@@ -0,0 +1,152 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead,BufReader,BufWriter,Write};
+use std::path::Path;
+
+use crate::dictionary::Error;
+use crate::dictionary::Feedback;
+use crate::dictionary::Result;
+use crate::dictionary::dictionary::worst_case;
+
+const ROOT: &str = "-";
+
+pub struct GuessTree
+{
+    guess: String,
+    children: HashMap<Feedback, GuessTree>
+}
+
+impl GuessTree {
+    pub(crate) fn build(candidates: &[String]) -> Result<GuessTree> {
+        if candidates.is_empty() {
+            return Err(Error::new("No candidate words to build a tree from."));
+        }
+        Ok(build_node(candidates))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<GuessTree> {
+        let file = File::open(path)?;
+        let mut root: Option<GuessTree> = None;
+
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (path, guess) = match line.split_once(' ') {
+                Some((path, guess)) => (path, guess.trim()),
+                None => {
+                    let msg = format!("Invalid guess tree line '{}'.", line);
+                    return Err(Error::new(&msg));
+                }
+            };
+
+            let node = GuessTree {
+                guess: String::from(guess),
+                children: HashMap::new()
+            };
+
+            if path == ROOT {
+                root = Some(node);
+                continue;
+            }
+
+            let mut parent = match root.as_mut() {
+                Some(root) => root,
+                None => return Err(Error::new("Guess tree has no root."))
+            };
+            let mut feedback = path.split('/').peekable();
+            while let Some(step) = feedback.next() {
+                let step = Feedback::parse(step)?;
+                if feedback.peek().is_none() {
+                    parent.children.insert(step, node);
+                    break;
+                }
+
+                parent = match parent.children.get_mut(&step) {
+                    Some(child) => child,
+                    None => {
+                        let msg = format!("Guess tree node '{}' has no \
+                                           parent.", path);
+                        return Err(Error::new(&msg));
+                    }
+                };
+            }
+        }
+
+        root.ok_or_else(|| Error::new("Guess tree is empty."))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "# girdle guess tree")?;
+        self.write(&mut writer, ROOT)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn guess(&self) -> &str {
+        &self.guess
+    }
+
+    pub fn next(&self, feedback: &Feedback) -> Option<&GuessTree> {
+        self.children.get(feedback)
+    }
+
+    pub fn depth(&self) -> usize {
+        1 + self.children.values()
+            .map(GuessTree::depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn write<W: Write>(&self, writer: &mut W, path: &str) -> Result<()> {
+        writeln!(writer, "{} {}", path, self.guess)?;
+
+        let mut children = self.children.iter()
+            .map(|(feedback, child)| (feedback.to_string(), child))
+            .collect::<Vec<_>>();
+        children.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (feedback, child) in children {
+            let path = match path {
+                ROOT => feedback,
+                path => format!("{}/{}", path, feedback)
+            };
+            child.write(writer, &path)?;
+        }
+        Ok(())
+    }
+}
+
+fn build_node(candidates: &[String]) -> GuessTree {
+    let guess = candidates.iter()
+        .min_by_key(|guess| worst_case(guess, candidates))
+        .cloned()
+        .unwrap_or_default();
+
+    let children = partition(&guess, candidates).into_iter()
+        .filter(|(feedback, _)| !feedback.is_solved())
+        .map(|(feedback, bucket)| (feedback, build_node(&bucket)))
+        .collect();
+
+    GuessTree { guess, children }
+}
+
+fn partition(guess: &str, candidates: &[String])
+    -> HashMap<Feedback, Vec<String>>
+{
+    let mut buckets: HashMap<Feedback, Vec<String>> = HashMap::new();
+    for answer in candidates {
+        buckets.entry(Feedback::compute(guess, answer))
+            .or_default()
+            .push(answer.clone());
+    }
+    buckets
+}
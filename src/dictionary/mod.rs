@@ -2,10 +2,37 @@
 // Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
 // 
 
+pub mod board_set;
 pub mod dictionary;
 pub mod error;
+pub mod feedback;
+pub mod guess_tree;
+pub mod state;
+pub mod warning;
+pub mod width;
 
 pub use error::Error;
 pub use error::Result;
+pub use board_set::BoardSet;
+pub use dictionary::Constraint;
 pub use dictionary::Dictionary;
+pub use state::DictionaryState;
+pub use feedback::Feedback;
+pub use feedback::LetterState;
+pub use guess_tree::GuessTree;
+pub use dictionary::FilterStats;
+pub use dictionary::MAX_LINE_LENGTH;
+pub use dictionary::GuessComparison;
+pub use dictionary::GuessMetrics;
+pub use dictionary::MatchInfo;
+pub use dictionary::MatchStream;
+pub use dictionary::Punctuation;
 pub use dictionary::SetType;
+pub use dictionary::SortMode;
+pub use dictionary::TieBreak;
+pub use dictionary::WordFilter;
+pub use dictionary::WordList;
+pub use warning::Warning;
+pub use dictionary::matrix_to_csv;
+pub use dictionary::read_frequencies;
+pub use width::display_width;
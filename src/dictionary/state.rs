@@ -0,0 +1,138 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::dictionary::Error;
+use crate::dictionary::Feedback;
+use crate::dictionary::Result;
+
+// Only the included and excluded letters and the position pattern are
+// parsed and displayed. The rest is filled in by `Dictionary::snapshot` so
+// that `Dictionary::restore` can put every constraint back. Positions are
+// 1-based, as in the `Dictionary` API.
+#[derive(Clone,Debug,Default,PartialEq,Eq)]
+pub struct DictionaryState
+{
+    pub include: Vec<char>,
+    pub exclude: Vec<char>,
+    pub positions: Option<Vec<char>>,
+    pub allowed: BTreeMap<usize, Vec<char>>,
+    pub not_at: BTreeMap<char, Vec<usize>>,
+    pub present: BTreeMap<char, Vec<usize>>,
+    pub min_counts: BTreeMap<char, usize>,
+    pub parity: BTreeMap<char, bool>,
+    pub adjacent: Vec<(char, char)>,
+    pub multiset: Option<String>,
+    pub intersection: Option<Vec<String>>,
+    pub tried: Vec<char>,
+    pub history: Vec<(String, Feedback)>
+}
+
+impl DictionaryState {
+    pub fn parse(s: &str) -> Result<DictionaryState> {
+        let mut state = DictionaryState::default();
+
+        for token in s.split_whitespace() {
+            if let Some(chars) = token.strip_prefix('+') {
+                state.include.extend(letters(token, chars)?);
+            } else if let Some(chars) = token.strip_prefix('-') {
+                state.exclude.extend(letters(token, chars)?);
+            } else {
+                if state.positions.is_some() {
+                    let msg = format!("Unexpected second position pattern \
+                                       '{}'.", token);
+                    return Err(Error::new(&msg));
+                }
+
+                let pattern = token.to_lowercase().chars().collect::<Vec<_>>();
+                if !pattern.iter().all(|ch| *ch == '.' || ch.is_alphabetic()) {
+                    let msg = format!("Invalid position pattern '{}'.", token);
+                    return Err(Error::new(&msg));
+                }
+                state.positions = Some(pattern);
+            }
+        }
+
+        state.include.sort();
+        state.include.dedup();
+        state.exclude.sort();
+        state.exclude.dedup();
+
+        if let Some(ch) = state.include.iter()
+            .find(|ch| state.exclude.contains(ch))
+        {
+            let msg = format!("'{}' is both included and excluded.", ch);
+            return Err(Error::new(&msg));
+        }
+
+        Ok(state)
+    }
+}
+
+impl TryFrom<&str> for DictionaryState {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<DictionaryState> {
+        DictionaryState::parse(s)
+    }
+}
+
+impl fmt::Display for DictionaryState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tokens = Vec::new();
+        if !self.include.is_empty() {
+            let include = self.include.iter().collect::<String>();
+            tokens.push(format!("+{}", include));
+        }
+        if !self.exclude.is_empty() {
+            let exclude = self.exclude.iter().collect::<String>();
+            tokens.push(format!("-{}", exclude));
+        }
+        if let Some(positions) = &self.positions {
+            tokens.push(positions.iter().collect());
+        }
+        write!(f, "{}", tokens.join(" "))
+    }
+}
+
+fn letters(token: &str, chars: &str) -> Result<Vec<char>> {
+    if chars.is_empty() || !chars.chars().all(char::is_alphabetic) {
+        let msg = format!("Invalid character set '{}'.", token);
+        return Err(Error::new(&msg));
+    }
+    Ok(chars.to_lowercase().chars().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_letters_and_positions() {
+        let state = DictionaryState::parse("+tsa -yr c....").unwrap();
+        assert_eq!(state.include, ['a', 's', 't']);
+        assert_eq!(state.exclude, ['r', 'y']);
+        assert_eq!(state.positions, Some("c....".chars().collect()));
+        assert_eq!(state.to_string(), "+ast -ry c....");
+        assert_eq!(DictionaryState::try_from("+AA -b").unwrap(),
+                   DictionaryState::parse("+a -b").unwrap());
+    }
+
+    #[test]
+    fn parse_empty() {
+        assert_eq!(DictionaryState::parse("  ").unwrap(),
+                   DictionaryState::default());
+        assert_eq!(DictionaryState::default().to_string(), "");
+    }
+
+    #[test]
+    fn parse_errors() {
+        for s in ["+", "-a1", "c.... .r...", "c.?..", "+ab -b"] {
+            assert!(DictionaryState::parse(s).is_err(), "{}", s);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::fmt;
+
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub enum Warning
+{
+    SkippedLine { line: usize, text: String },
+    Duplicate { line: usize, word: String },
+    InvalidEncoding { line: usize }
+}
+
+impl Warning {
+    pub fn line(&self) -> usize {
+        match self {
+            Warning::SkippedLine { line, .. } => *line,
+            Warning::Duplicate { line, .. } => *line,
+            Warning::InvalidEncoding { line } => *line
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::SkippedLine { line, text } => {
+                write!(f, "line {}: skipped '{}'", line, text)
+            },
+            Warning::Duplicate { line, word } => {
+                write!(f, "line {}: duplicate word '{}'", line, word)
+            },
+            Warning::InvalidEncoding { line } => {
+                write!(f, "line {}: invalid UTF-8 was replaced", line)
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+// Widths are only used to pad the results into columns, so the tables cover
+// the marks and wide characters found in word lists rather than all of
+// Unicode; a character they miss is counted as one cell and at worst shifts
+// its column. The ranges must stay sorted for the binary search.
+
+// Combining marks that render without advancing the cursor.
+const ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0300, 0x036f), (0x0483, 0x0489), (0x0591, 0x05bd), (0x05bf, 0x05bf),
+    (0x05c1, 0x05c2), (0x05c4, 0x05c5), (0x05c7, 0x05c7), (0x0610, 0x061a),
+    (0x064b, 0x065f), (0x0670, 0x0670), (0x06d6, 0x06dc), (0x0900, 0x0903),
+    (0x093a, 0x094f), (0x0e31, 0x0e31), (0x0e34, 0x0e3a), (0x0e47, 0x0e4e),
+    (0x1ab0, 0x1aff), (0x1dc0, 0x1dff), (0x200b, 0x200f), (0x20d0, 0x20ff),
+    (0xfe00, 0xfe0f), (0xfe20, 0xfe2f)
+];
+
+// East Asian wide and fullwidth characters that take two cells.
+const DOUBLE_WIDTH: &[(u32, u32)] = &[
+    (0x1100, 0x115f), (0x2e80, 0x303e), (0x3041, 0x33ff), (0x3400, 0x4dbf),
+    (0x4e00, 0x9fff), (0xa000, 0xa4cf), (0xac00, 0xd7a3), (0xf900, 0xfaff),
+    (0xfe30, 0xfe4f), (0xff00, 0xff60), (0xffe0, 0xffe6), (0x1f300, 0x1f64f),
+    (0x1f900, 0x1f9ff), (0x20000, 0x2fffd), (0x30000, 0x3fffd)
+];
+
+pub fn display_width(s: &str) -> usize {
+    if s.is_ascii() {
+        return s.len();
+    }
+    s.chars().map(char_width).sum()
+}
+
+fn char_width(ch: char) -> usize {
+    let code = ch as u32;
+    if in_ranges(code, ZERO_WIDTH) {
+        0
+    } else if in_ranges(code, DOUBLE_WIDTH) {
+        2
+    } else {
+        1
+    }
+}
+
+fn in_ranges(code: u32, ranges: &[(u32, u32)]) -> bool {
+    ranges.binary_search_by(|(start, end)| {
+        if code < *start {
+            std::cmp::Ordering::Greater
+        } else if code > *end {
+            std::cmp::Ordering::Less
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    }).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_is_one_cell_per_byte() {
+        assert_eq!(display_width("crane"), 5);
+        assert_eq!(display_width(""), 0);
+    }
+
+    #[test]
+    fn wide_characters_take_two_cells() {
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("한글"), 4);
+        assert_eq!(display_width("ｗｏｒｄ"), 8);
+    }
+
+    #[test]
+    fn combining_marks_take_no_cells() {
+        assert_eq!(display_width("cafe\u{301}"), 4);
+        assert_eq!(display_width("café"), 4);
+        assert_eq!(display_width("n\u{303}a\u{308}"), 2);
+    }
+
+    #[test]
+    fn combining_marks_of_other_scripts_take_no_cells() {
+        // Hebrew points, Arabic harakat and Thai vowel and tone marks.
+        assert_eq!(display_width("\u{5e9}\u{5c1}\u{5b8}"), 1);
+        assert_eq!(display_width("\u{643}\u{64e}\u{62a}\u{64e}"), 2);
+        assert_eq!(display_width("\u{e01}\u{e34}\u{e48}"), 1);
+        // Enclosing marks, variation selectors and zero width joiners.
+        assert_eq!(display_width("a\u{20dd}"), 1);
+        assert_eq!(display_width("a\u{fe0f}b\u{200d}c"), 3);
+    }
+
+    #[test]
+    fn ranges_are_sorted() {
+        for ranges in [ZERO_WIDTH, DOUBLE_WIDTH] {
+            for (start, end) in ranges {
+                assert!(start <= end);
+            }
+            for pair in ranges.windows(2) {
+                assert!(pair[0].1 < pair[1].0);
+            }
+        }
+    }
+}
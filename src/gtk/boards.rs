@@ -0,0 +1,235 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::borrow::Borrow;
+use std::rc::Rc;
+
+use gtk::atk::prelude::*;
+use gtk::prelude::*;
+
+use girdle::dictionary::BoardSet;
+use girdle::dictionary::Feedback;
+use girdle::dictionary::SortMode;
+
+const RESULTS_PER_BOARD: usize = 200;
+
+struct Column {
+    feedback: gtk::Entry,
+    count: gtk::Label,
+    results: gtk::TextView
+}
+
+pub struct Boards {
+    boards: BoardSet,
+    window: gtk::Window,
+    guess: gtk::Entry,
+    apply: gtk::Button,
+    reset: gtk::Button,
+    status: gtk::Label,
+    columns: Vec<Column>
+}
+
+impl Boards {
+    pub fn new(boards: BoardSet, parent: Option<&gtk::Window>) -> Rc<Boards> {
+        for board in boards.boards() {
+            board.set_sort_mode(SortMode::ByScore);
+        }
+
+        let window = gtk::Window::new(gtk::WindowType::Toplevel);
+        window.set_title(&format!("Girdle: {} Boards", boards.len()));
+        window.set_transient_for(parent);
+        window.set_destroy_with_parent(true);
+        window.set_border_width(8);
+        window.set_default_size(200 * boards.len() as i32, 480);
+
+        let guess = gtk::Entry::new();
+        guess.set_max_length(boards.length() as i32);
+
+        let columns = (0..boards.len())
+            .map(|_| {
+                let feedback = gtk::Entry::new();
+                feedback.set_max_length(boards.length() as i32);
+                feedback.set_placeholder_text(Some("G/Y/X"));
+
+                let results = gtk::TextView::new();
+                results.set_cursor_visible(false);
+                results.set_editable(false);
+
+                Column {
+                    feedback,
+                    count: gtk::Label::new(None),
+                    results
+                }
+            })
+            .collect();
+
+        let boards = Boards {
+            boards,
+            window,
+            guess,
+            apply: gtk::Button::with_mnemonic("_Apply"),
+            reset: gtk::Button::with_mnemonic("_Reset"),
+            status: gtk::Label::new(None),
+            columns
+        };
+
+        let boards = Rc::new(boards);
+        boards.build();
+        boards.display_results();
+        boards_connect_activate(&boards);
+        boards_connect_reset(&boards);
+        boards
+    }
+
+    pub fn present(&self) {
+        self.window.show_all();
+        self.window.present();
+    }
+
+    pub fn close(&self) {
+        unsafe { self.window.destroy(); }
+    }
+
+    fn build(&self) {
+        let vbox = gtk::Box::new(gtk::Orientation::Vertical, 8);
+
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        let label = gtk::Label::with_mnemonic("G_uess");
+        label.set_mnemonic_widget(Some(&self.guess));
+        hbox.add(&label);
+        hbox.pack_start(&self.guess, true, true, 0);
+        hbox.add(&self.apply);
+        hbox.add(&self.reset);
+        vbox.add(&hbox);
+
+        let panes = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        panes.set_homogeneous(true);
+        for (i, column) in self.columns.iter().enumerate() {
+            let frame = gtk::Frame::new(Some(&format!("Board {}", i + 1)));
+            let inner = gtk::Box::new(gtk::Orientation::Vertical, 8);
+            inner.set_border_width(4);
+            if let Some(accessible) = column.feedback.accessible() {
+                accessible.set_name(&format!("Board {} feedback", i + 1));
+            }
+            inner.add(&column.feedback);
+            inner.add(&column.count);
+
+            let none = gtk::Adjustment::NONE;
+            let scrolled = gtk::ScrolledWindow::new(none, none);
+            scrolled.set_shadow_type(gtk::ShadowType::In);
+            scrolled.add(&column.results);
+            inner.pack_start(&scrolled, true, true, 0);
+
+            frame.add(&inner);
+            panes.pack_start(&frame, true, true, 0);
+        }
+        vbox.pack_start(&panes, true, true, 0);
+        vbox.add(&self.status);
+
+        self.window.add(&vbox);
+    }
+
+    fn apply_guess(&self) {
+        let guess = self.guess.text().to_lowercase();
+        let mut feedback = Vec::new();
+        for (i, column) in self.columns.iter().enumerate() {
+            let text = column.feedback.text();
+            if text.is_empty() || self.boards.is_solved(i) {
+                feedback.push(None);
+                continue;
+            }
+
+            match Feedback::parse(&text) {
+                Ok(parsed) => feedback.push(Some(parsed)),
+                Err(error) => {
+                    gdk::beep();
+                    self.status.set_text(&format!("Board {}: {}", i + 1,
+                                                  error));
+                    return;
+                }
+            }
+        }
+
+        if feedback.iter().all(Option::is_none) {
+            gdk::beep();
+            self.status.set_text("Enter feedback for at least one board.");
+            return;
+        }
+
+        if let Err(error) = self.boards.apply_guess(&guess, &feedback) {
+            gdk::beep();
+            self.status.set_text(&format!("{}", error));
+            return;
+        }
+
+        self.guess.set_text("");
+        for column in &self.columns {
+            column.feedback.set_text("");
+        }
+        self.display_results();
+        self.status.set_text(&format!("Applied '{}'.", guess));
+    }
+
+    fn reset(&self) {
+        self.boards.reset();
+        self.guess.set_text("");
+        for column in &self.columns {
+            column.feedback.set_text("");
+        }
+        self.display_results();
+        self.status.set_text("Reset all boards.");
+    }
+
+    fn display_results(&self) {
+        for (i, column) in self.columns.iter().enumerate() {
+            let matches = self.boards.board(i).matches_answers_only();
+            let solved = self.boards.is_solved(i);
+
+            column.feedback.set_sensitive(!solved);
+            column.count.set_text(&match solved {
+                true => String::from("Solved"),
+                false => format!("{} matches", matches.len())
+            });
+
+            let mut results = String::new();
+            for word in matches.iter().take(RESULTS_PER_BOARD) {
+                results.push_str(&format!("{}\n", word));
+            }
+
+            let buffer = column.results.buffer()
+                .expect("Couldn't get results buffer.");
+            buffer.set_text(&results);
+        }
+    }
+}
+
+fn boards_connect_activate(boards: &Rc<Boards>) {
+    let boards_ptr = Rc::downgrade(boards);
+    boards.apply.connect_clicked(move |_| {
+        let rc = boards_ptr.upgrade().unwrap();
+        let boards: &Boards = rc.borrow();
+        boards.apply_guess();
+    });
+
+    let entries = boards.columns.iter()
+        .map(|column| &column.feedback)
+        .chain(std::iter::once(&boards.guess));
+    for entry in entries {
+        let boards_ptr = Rc::downgrade(boards);
+        entry.connect_activate(move |_| {
+            let rc = boards_ptr.upgrade().unwrap();
+            let boards: &Boards = rc.borrow();
+            boards.apply_guess();
+        });
+    }
+}
+
+fn boards_connect_reset(boards: &Rc<Boards>) {
+    let boards_ptr = Rc::downgrade(boards);
+    boards.reset.connect_clicked(move |_| {
+        let rc = boards_ptr.upgrade().unwrap();
+        let boards: &Boards = rc.borrow();
+        boards.reset();
+    });
+}
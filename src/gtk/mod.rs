@@ -4,105 +4,109 @@
 
 use std::process;
 use std::borrow::Borrow;
-use std::cell::{Ref,RefCell};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
+#[cfg(feature = "watch")]
+use std::time::Duration;
 
-use gdk;
-use glib::signal;
-use glib::SignalHandlerId;
+#[cfg(feature = "watch")]
+use gtk::gio;
 use gtk::prelude::*;
 
-use crate::Dictionary;
-use crate::dictionary::Error;
-use crate::dictionary::SetType;
-
-use crate::DICTIONARIES;
-
-struct DeleteSignalIds {
-    exclude: RefCell<Option<SignalHandlerId>>,
-    include: RefCell<Option<SignalHandlerId>>
-}
+use girdle::Dictionary;
+use girdle::dictionary::BoardSet;
+use girdle::dictionary::Error;
+use girdle::dictionary::Warning;
+use girdle::dictionary::WordList;
+use girdle::dictionary::SortMode;
+
+use crate::{DEFAULT_LENGTH,WORD_LENGTHS};
+use crate::cli::Args;
+use crate::config::Config;
+use crate::config_path;
+use crate::load_word_list_with_warnings;
+
+mod boards;
+#[cfg(feature = "ocr")]
+mod ocr;
+mod tab;
+use boards::Boards;
+use tab::Tab;
+
+#[cfg(feature = "watch")]
+const RELOAD_DELAY: Duration = Duration::from_millis(500);
+
+const ANNOTATED_MATCHES: usize = 200;
+const ELIMINATION_RANKS: usize = 200;
+const RESULT_COLUMNS: &[usize] = &[1, 2, 3, 4, 6];
+const WARNINGS_SHOWN: usize = 5;
+
+const BOARD_COUNTS: &[(usize, &str)] = &[
+    (2, "_Dordle (2 Boards)..."),
+    (4, "_Quordle (4 Boards)...")
+];
 
 pub struct UI {
-    dictionary: Dictionary,
-    signal_ids: DeleteSignalIds,
     application: gtk::Application,
-    include: gtk::Entry,
-    exclude: gtk::Entry,
-    results: gtk::TextView,
-    positions: [gtk::Entry; 5]
-}
-
-impl DeleteSignalIds {
-    pub fn new() -> DeleteSignalIds {
-        DeleteSignalIds {
-            exclude: RefCell::new(None),
-            include: RefCell::new(None)
-        }
-    }
-
-    pub fn signal(&self, hook_type: SetType) -> Ref<SignalHandlerId> {
-        match hook_type {
-            SetType::Excluded => Ref::map(self.exclude.borrow(), |v|
-                                          v.as_ref().unwrap()),
-            SetType::Included => Ref::map(self.include.borrow(), |v|
-                                          v.as_ref().unwrap())
-        }
-    }
+    accel_group: gtk::AccelGroup,
+    notebook: gtk::Notebook,
+    statusbar: gtk::Statusbar,
+    config: RefCell<Config>,
+    word_list: RefCell<WordList>,
+    warnings: Vec<Warning>,
+    answers: Option<WordList>,
+    boards: RefCell<Option<Rc<Boards>>>,
+    #[cfg(feature = "watch")]
+    monitor: RefCell<Option<gio::FileMonitor>>,
+    #[cfg(feature = "watch")]
+    pending_reload: RefCell<Option<glib::SourceId>>,
+    tabs: Vec<Rc<Tab>>
 }
 
 impl UI {
-    pub fn run(id: &str) {
-        gtk::init().expect("Cannot initialize GTK.");
+    pub fn init() -> Result<(), glib::BoolError> {
+        gtk::init()
+    }
 
-        let result = Dictionary::new(DICTIONARIES);
+    pub fn run(id: &str, args: &Args) {
+        let result = load_word_list_with_warnings(args.dict.as_deref());
         if let Err(error) = result {
             show_error_dialog(id, error);
         }
+        let (word_list, warnings) = result.unwrap();
 
-        let ui = new_ui(id, result.unwrap());
-        let include = connect_delete_text(SetType::Included, &ui);
-        let exclude = connect_delete_text(SetType::Excluded, &ui);
-        ui.set_signal_ids(include, exclude);
+        let answers = match args.load_answers() {
+            Ok(answers) => answers,
+            Err(error) => show_error_dialog(id, error)
+        };
 
-        connect_focus_out_event(SetType::Included, &ui);
-        connect_insert_text(SetType::Included, &ui);
+        let frequencies = match args.load_frequencies() {
+            Ok(frequencies) => frequencies,
+            Err(error) => show_error_dialog(id, error)
+        };
 
-        connect_focus_out_event(SetType::Excluded, &ui);
-        connect_insert_text(SetType::Excluded, &ui);
-
-        position_connect_delete_text(&ui);
-        position_connect_focus_out_event(&ui);
-        position_connect_insert_text(&ui);
+        let ui = new_ui(id, word_list, warnings, answers, frequencies, args);
         application_connect_activate(&ui);
-        ui.application.run();
+        ui.application.run_with_args::<&str>(&[]);
     }
 
-    fn refresh(&self) {
-        let chars = self.dictionary.excluded_chars();
-        let mut excluded = String::new();
-        for ch in chars.iter() { excluded.push(*ch); }
-
-        let chars = self.dictionary.included_chars();
-        let mut included = String::new();
-        for ch in chars.iter() { included.push(*ch); }
-
-        let signal_id = self.signal_ids.signal(SetType::Excluded);
-        self.exclude.block_signal(&signal_id);
-        self.exclude.set_text(&excluded);
-        self.exclude.unblock_signal(&signal_id);
+    fn save_config(&self) {
+        let path = match config_path() {
+            Some(path) => path,
+            None => return
+        };
 
-        let signal_id = self.signal_ids.signal(SetType::Included);
-        self.include.block_signal(&signal_id);
-        self.include.set_text(&included);
-        self.include.unblock_signal(&signal_id);
+        if let Err(error) = self.config.borrow().save(&path) {
+            let context_id = self.statusbar.context_id("config");
+            let msg = format!("Unable to save {}: {}", path, error);
+            self.statusbar.push(context_id, &msg);
+        }
     }
 
-    fn set_signal_ids(&self, include: SignalHandlerId,
-                             exclude: SignalHandlerId)
-    {
-        *self.signal_ids.include.borrow_mut() = Some(include);
-        *self.signal_ids.exclude.borrow_mut() = Some(exclude);
+    fn current_tab(&self) -> Option<&Rc<Tab>> {
+        let page = self.notebook.current_page()?;
+        self.tabs.get(page as usize)
     }
 }
 
@@ -124,33 +128,91 @@ fn show_error_dialog(id: &str, error: Error) -> ! {
         });
         dialog.show_all();
     });
-    application.run();
+    application.run_with_args::<&str>(&[]);
     process::exit(1);
 }
 
-fn new_ui(id: &str, dictionary: Dictionary) -> Rc<UI> {
+fn show_reload_error(ui: &UI, error: &Error) {
+    let window = ui.notebook.toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+
+    let dialog = gtk::MessageDialog::builder()
+        .buttons(gtk::ButtonsType::Ok)
+        .message_type(gtk::MessageType::Warning)
+        .text("Unable to reload the dictionary")
+        .secondary_text(&format!("{}\n\nThe current word list has been kept.",
+                                 error))
+        .modal(true)
+        .title("Reload Failed")
+        .build();
+    dialog.set_transient_for(window.as_ref());
+
+    dialog.run();
+    unsafe { dialog.destroy(); }
+}
+
+fn new_ui(id: &str, word_list: WordList, warnings: Vec<Warning>,
+          answers: Option<WordList>,
+          frequencies: Option<HashMap<String, u64>>, args: &Args) -> Rc<UI>
+{
     let application = gtk::Application::new(Some(id), Default::default());
 
-    let mut vec = Vec::new();
-    for i in 0usize..5 {
-        let entry = gtk::Entry::new();
-        entry.set_max_length(1);
-        unsafe { entry.set_data("index", i); }
-        vec.push(entry);
-    }
+    let statusbar = gtk::Statusbar::new();
+    let context_id = statusbar.context_id("load");
+    statusbar.push(context_id, &format!("Loaded {} words from {}.",
+                                        word_list.len(),
+                                        word_list.path().display()));
+
+    let config = match config_path().map(Config::load) {
+        Some(Ok(config)) => config,
+        Some(Err(error)) => {
+            let context_id = statusbar.context_id("config");
+            statusbar.push(context_id,
+                           &format!("Unable to load settings: {}", error));
+            Config::default()
+        },
+        None => Config::default()
+    };
 
-    let results = gtk::TextView::new();
-    results.set_cursor_visible(false);
-    results.set_editable(false);
+    let mut tabs = Vec::new();
+    for length in WORD_LENGTHS {
+        let dictionary = Dictionary::from_word_list(&word_list, *length);
+        if let Some(answers) = &answers {
+            dictionary.set_answers(answers.words());
+        }
+        if let Some(frequencies) = &frequencies {
+            dictionary.set_frequencies(frequencies);
+        }
+
+        let preset = *length == args.length && args.has_constraints();
+        if preset {
+            args.apply(&dictionary);
+        }
+
+        let tab = Tab::new(dictionary, &statusbar);
+        tab.set_results_font(&config.results_font);
+        tab.set_result_columns(config.result_columns);
+        if preset {
+            tab.sync();
+        }
+        tabs.push(tab);
+    }
 
     let ui = UI {
-        dictionary: dictionary,
         application: application,
-        include: gtk::Entry::new(),
-        exclude: gtk::Entry::new(),
-        results: results,
-        signal_ids: DeleteSignalIds::new(),
-        positions: vec.try_into().unwrap()
+        accel_group: gtk::AccelGroup::new(),
+        notebook: gtk::Notebook::new(),
+        statusbar: statusbar,
+        config: RefCell::new(config),
+        word_list: RefCell::new(word_list),
+        warnings,
+        answers: answers,
+        boards: RefCell::new(None),
+        #[cfg(feature = "watch")]
+        monitor: RefCell::new(None),
+        #[cfg(feature = "watch")]
+        pending_reload: RefCell::new(None),
+        tabs: tabs
     };
 
     Rc::new(ui)
@@ -162,6 +224,15 @@ fn build_menubar(ui: &Rc<UI>) -> gtk::MenuBar {
 
     let file = gtk::MenuItem::with_mnemonic("_File");
     let reset = gtk::MenuItem::with_mnemonic("_Reset");
+    let clear_excludes = gtk::MenuItem::with_mnemonic(
+        "Clear E_xcluded Characters");
+    let reload = gtk::MenuItem::with_mnemonic("Re_load Dictionary");
+    let emoji = gtk::MenuItem::with_mnemonic("Copy _Emoji Grid");
+    let markdown = gtk::MenuItem::with_mnemonic("Copy _Markdown Table");
+    let save = gtk::MenuItem::with_mnemonic(
+        "_Save Candidates as Dictionary...");
+    #[cfg(feature = "ocr")]
+    let import = gtk::MenuItem::with_mnemonic("_Import Screenshot...");
     let quit = gtk::MenuItem::with_mnemonic("_Quit");
 
     let ui_ptr = Rc::downgrade(ui);
@@ -169,18 +240,85 @@ fn build_menubar(ui: &Rc<UI>) -> gtk::MenuBar {
         let rc = ui_ptr.upgrade().unwrap();
         let ui: &UI = rc.borrow();
 
-        ui.dictionary.reset();
-        ui.refresh();
+        if let Some(tab) = ui.current_tab() {
+            tab.reset();
+        }
+    });
+
+    let ui_ptr = Rc::downgrade(ui);
+    clear_excludes.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+
+        if let Some(tab) = ui.current_tab() {
+            tab.clear_excludes();
+        }
+    });
+
+    let ui_ptr = Rc::downgrade(ui);
+    reload.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+
+        let mut failure = reload_word_list(ui).err();
+        for tab in &ui.tabs {
+            if let Err(error) = tab.reload() {
+                failure.get_or_insert(error);
+            }
+        }
+
+        if let Some(error) = failure {
+            show_reload_error(ui, &error);
+        }
+    });
+
+    let ui_ptr = Rc::downgrade(ui);
+    emoji.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
 
-        for pos in 0..5 {
-            ui.positions[pos].set_text("");
+        if let Some(tab) = ui.current_tab() {
+            tab.copy_emoji_grid();
         }
+    });
+
+    let ui_ptr = Rc::downgrade(ui);
+    markdown.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
 
-        let buffer = ui.results.buffer()
-            .expect("Couldn't get results buffer.");
-        buffer.set_text("");
+        if let Some(tab) = ui.current_tab() {
+            tab.copy_markdown_table();
+        }
     });
 
+    let ui_ptr = Rc::downgrade(ui);
+    save.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+
+        if let Some(tab) = ui.current_tab() {
+            let window = ui.notebook.toplevel()
+                .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+            tab.save_candidates(window.as_ref());
+        }
+    });
+
+    #[cfg(feature = "ocr")]
+    {
+        let ui_ptr = Rc::downgrade(ui);
+        import.connect_activate(move |_| {
+            let rc = ui_ptr.upgrade().unwrap();
+            let ui: &UI = rc.borrow();
+
+            if let Some(tab) = ui.current_tab() {
+                let window = ui.notebook.toplevel()
+                    .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+                tab.import_screenshot(window.as_ref());
+            }
+        });
+    }
+
     let ui_ptr = Rc::downgrade(ui);
     quit.connect_activate(move |_| {
         let rc = ui_ptr.upgrade().unwrap();
@@ -191,223 +329,826 @@ fn build_menubar(ui: &Rc<UI>) -> gtk::MenuBar {
 
     file.set_submenu(Some(&file_menu));
     file_menu.append(&reset);
+    file_menu.append(&clear_excludes);
+    file_menu.append(&reload);
+    file_menu.append(&emoji);
+    file_menu.append(&markdown);
+    file_menu.append(&save);
+    #[cfg(feature = "ocr")]
+    file_menu.append(&import);
     file_menu.append(&quit);
     menubar.add(&file);
 
+    let view = gtk::MenuItem::with_mnemonic("_View");
+    view.set_submenu(Some(&build_view_menu(ui)));
+    menubar.add(&view);
+
+    let go = gtk::MenuItem::with_mnemonic("_Go");
+    go.set_submenu(Some(&build_go_menu(ui)));
+    menubar.add(&go);
+
+    let tools = gtk::MenuItem::with_mnemonic("_Tools");
+    tools.set_submenu(Some(&build_tools_menu(ui)));
+    menubar.add(&tools);
+
     menubar
 }
 
-fn build_ui(ui: &Rc<UI>) -> gtk::Box {
-    let vbox = gtk::Box::new(gtk::Orientation::Vertical, 8);
-    let menubar = build_menubar(ui);
-    vbox.add(&menubar);
+fn build_go_menu(ui: &Rc<UI>) -> gtk::Menu {
+    let go_menu = gtk::Menu::new();
+    let include = gtk::MenuItem::with_mnemonic("_Correct Characters");
 
-    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 8);
-    let include = build_character_entry("Correct Characters", &ui.include);
-    hbox.pack_start(&include, true, true, 0);
-    let exclude = build_character_entry("Incorrect Characters", &ui.exclude);
-    hbox.pack_start(&exclude, true, true, 0);
-    vbox.add(&hbox);
-
-    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 8);
-    let label = gtk::Label::new(Some("Exact Positions"));
-    hbox.add(&label);
-    vbox.add(&hbox);
-
-    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 8);
-    for i in 0..5 {
-        hbox.pack_start(&ui.positions[i], true, false, 0);
-    }
-    vbox.add(&hbox);
+    let (key, modifier) = gtk::accelerator_parse("<Control>i");
+    include.add_accelerator("activate", &ui.accel_group, key, modifier,
+                            gtk::AccelFlags::VISIBLE);
+
+    let ui_ptr = Rc::downgrade(ui);
+    include.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
 
-    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 8);
-    let label = gtk::Label::new(Some("Results"));
+        if let Some(tab) = ui.current_tab() {
+            tab.focus_include();
+        }
+    });
 
-    hbox.add(&label);
-    vbox.add(&hbox);
+    let suggestion = gtk::MenuItem::with_mnemonic("Use _Suggestion");
+    let (key, modifier) = gtk::accelerator_parse("<Control>u");
+    suggestion.add_accelerator("activate", &ui.accel_group, key, modifier,
+                               gtk::AccelFlags::VISIBLE);
 
-    let none = gtk::Adjustment::NONE;
-    let window = gtk::ScrolledWindow::new(none, none);
-    window.set_shadow_type(gtk::ShadowType::In);
-    window.add(&ui.results);
-    vbox.pack_start(&window, true, true, 0);
+    let ui_ptr = Rc::downgrade(ui);
+    suggestion.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
 
-    return vbox;
-}
+        if let Some(tab) = ui.current_tab() {
+            tab.use_suggestion();
+        }
+    });
 
-fn build_character_entry(label: &str, entry: &gtk::Entry) -> gtk::Box {
-    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 8);
-    let vbox = gtk::Box::new(gtk::Orientation::Vertical, 8);
-    let label = gtk::Label::new(Some(label));
-    vbox.add(&hbox);
-    hbox.add(&label);
-    vbox.add(entry);
-    vbox
-}
+    let frequency = gtk::MenuItem::with_mnemonic(
+        "Suggest by Letter _Frequency");
 
-fn application_connect_activate(ui: &Rc<UI>) {
-    let vbox = build_ui(&ui);
-    ui.application.connect_activate(move |app| {
-        let window = gtk::ApplicationWindow::new(app);
+    let ui_ptr = Rc::downgrade(ui);
+    frequency.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
 
-        window.set_title("Girdle");
-        window.set_border_width(8);
-        window.set_position(gtk::WindowPosition::Center);
-        window.add(&vbox);
-        window.show_all();
+        if let Some(tab) = ui.current_tab() {
+            tab.use_frequency_suggestion();
+        }
     });
+
+    go_menu.append(&include);
+    go_menu.append(&suggestion);
+    go_menu.append(&frequency);
+    go_menu
 }
 
-fn is_non_include_character(ch: char) -> bool {
-    !(ch.is_ascii_alphabetic() || ch == ',' || ch == ' ')
+fn build_tools_menu(ui: &Rc<UI>) -> gtk::Menu {
+    let tools_menu = gtk::Menu::new();
+    let difficulty = gtk::MenuItem::with_mnemonic("Word _Difficulty...");
+
+    let ui_ptr = Rc::downgrade(ui);
+    difficulty.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        show_word_difficulty(ui);
+    });
+
+    tools_menu.append(&difficulty);
+
+    let elimination = gtk::MenuItem::with_mnemonic("Rank by _Elimination...");
+
+    let ui_ptr = Rc::downgrade(ui);
+    elimination.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        show_elimination_ranking(ui);
+    });
+
+    tools_menu.append(&elimination);
+
+    let annotated = gtk::MenuItem::with_mnemonic("_Annotate Candidates...");
+
+    let ui_ptr = Rc::downgrade(ui);
+    annotated.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        show_annotated_matches(ui);
+    });
+
+    tools_menu.append(&annotated);
+
+    let compare = gtk::MenuItem::with_mnemonic("Com_pare Guesses...");
+
+    let ui_ptr = Rc::downgrade(ui);
+    compare.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        show_guess_comparison(ui);
+    });
+
+    tools_menu.append(&compare);
+    tools_menu.append(&gtk::SeparatorMenuItem::new());
+
+    for (count, label) in BOARD_COUNTS {
+        let item = gtk::MenuItem::with_mnemonic(label);
+        let count = *count;
+        let ui_ptr = Rc::downgrade(ui);
+        item.connect_activate(move |_| {
+            let rc = ui_ptr.upgrade().unwrap();
+            let ui: &UI = rc.borrow();
+            show_boards(ui, count);
+        });
+        tools_menu.append(&item);
+    }
+    tools_menu
 }
 
-fn display_results(dict: &Dictionary, results: &gtk::TextView) {
-    let matches =  dict.matches();
-    let buffer = results.buffer()
-        .expect("Couldn't get results buffer.");
-
-    match &*matches {
-        Some(matches) => {
-            let mut results = String::new();
-            for word in &*matches {
-                let s = format!("{}\n", word);
-                results.push_str(&s);
-            }
-            buffer.set_text(&results);
-        },
+fn build_view_menu(ui: &Rc<UI>) -> gtk::Menu {
+    let view_menu = gtk::Menu::new();
+    let font = gtk::MenuItem::with_mnemonic("Results _Font...");
 
-        None => {
-            buffer.set_text("")
+    let ui_ptr = Rc::downgrade(ui);
+    font.connect_activate(move |_| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        choose_results_font(ui);
+    });
+    view_menu.append(&font);
+
+    let columns = gtk::MenuItem::with_mnemonic("Result _Columns");
+    columns.set_submenu(Some(&build_columns_menu(ui)));
+    view_menu.append(&columns);
+
+    let keep_above = gtk::CheckMenuItem::with_mnemonic("Always on _Top");
+    keep_above.set_active(ui.config.borrow().keep_above);
+
+    let ui_ptr = Rc::downgrade(ui);
+    keep_above.connect_toggled(move |item| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+
+        let window = ui.notebook.toplevel()
+            .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+        if let Some(window) = window {
+            window.set_keep_above(item.is_active());
         }
+        ui.config.borrow_mut().keep_above = item.is_active();
+        ui.save_config();
+    });
+    view_menu.append(&keep_above);
+    view_menu.append(&gtk::SeparatorMenuItem::new());
+
+    let sort_modes = [
+        (SortMode::Dictionary, "_Dictionary Order"),
+        (SortMode::Alphabetical, "_Alphabetical"),
+        (SortMode::ByFrequency, "By Letter _Frequency"),
+        (SortMode::ByScore, "By Letter _Score")
+    ];
+
+    let mut group: Option<gtk::RadioMenuItem> = None;
+    for (sort_mode, label) in sort_modes {
+        let item = match &group {
+            Some(group) =>
+                gtk::RadioMenuItem::with_mnemonic_from_widget(group,
+                                                              Some(label)),
+            None => gtk::RadioMenuItem::with_mnemonic(label)
+        };
+
+        let ui_ptr = Rc::downgrade(ui);
+        item.connect_toggled(move |item| {
+            if !item.is_active() { return; }
+
+            let rc = ui_ptr.upgrade().unwrap();
+            let ui: &UI = rc.borrow();
+            for tab in &ui.tabs {
+                tab.set_sort_mode(sort_mode);
+            }
+        });
+
+        view_menu.append(&item);
+        group.get_or_insert(item);
     }
-}
 
-fn connect_delete_text(hook_type: SetType, ui: &Rc<UI>) -> SignalHandlerId {
-    let entry = match hook_type {
-        SetType::Excluded => &ui.exclude,
-        SetType::Included => &ui.include
-    };
+    view_menu.append(&gtk::SeparatorMenuItem::new());
+    let inverted = gtk::CheckMenuItem::with_mnemonic("Show _Rejected Words");
 
     let ui_ptr = Rc::downgrade(ui);
-    let id = entry.connect_delete_text(move |entry, start, end| {
+    inverted.connect_toggled(move |item| {
         let rc = ui_ptr.upgrade().unwrap();
         let ui: &UI = rc.borrow();
+        for tab in &ui.tabs {
+            tab.set_inverted(item.is_active());
+        }
+    });
+    view_menu.append(&inverted);
 
-        let gstring = entry.text();
-        let s = gstring.as_str();
-        let start: usize = start.try_into().unwrap();
-        let end: usize = end.try_into().unwrap();
+    let keep_greens = gtk::CheckMenuItem::with_mnemonic(
+        "Keep _Green Letters When Excluded");
 
-        for ch in s[start..end].chars() {
-            ui.dictionary.remove_char(hook_type, ch);
+    let ui_ptr = Rc::downgrade(ui);
+    keep_greens.connect_toggled(move |item| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        for tab in &ui.tabs {
+            tab.set_keep_greens(item.is_active());
         }
-        display_results(&ui.dictionary, &ui.results);
     });
+    view_menu.append(&keep_greens);
 
-    return id;
-}
+    let hide_guessed = gtk::CheckMenuItem::with_mnemonic(
+        "_Hide Guessed Words");
 
-fn connect_focus_out_event(hook_type: SetType, ui: &Rc<UI>) {
-    let entry = match hook_type {
-        SetType::Excluded => &ui.exclude,
-        SetType::Included => &ui.include
-    };
+    let ui_ptr = Rc::downgrade(ui);
+    hide_guessed.connect_toggled(move |item| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        for tab in &ui.tabs {
+            tab.set_hide_guessed(item.is_active());
+        }
+    });
+    view_menu.append(&hide_guessed);
+
+    let accumulate = gtk::CheckMenuItem::with_mnemonic(
+        "Acc_umulate Excluded Characters");
 
     let ui_ptr = Rc::downgrade(ui);
-    entry.connect_focus_out_event(move |entry, _| {
+    accumulate.connect_toggled(move |item| {
         let rc = ui_ptr.upgrade().unwrap();
         let ui: &UI = rc.borrow();
+        for tab in &ui.tabs {
+            tab.set_accumulate_excludes(item.is_active());
+        }
+    });
+    view_menu.append(&accumulate);
 
-        ui.dictionary.clear_set(hook_type);
+    let impossible = gtk::CheckMenuItem::with_mnemonic(
+        "Mark _Impossible Letters");
 
-        let gstring = entry.text();
-        let text = gstring.as_str();
-        for ch in text.chars() {
-            if ch.is_ascii_alphabetic() {
-                ui.dictionary.add_char(hook_type, ch);
-            }
+    let ui_ptr = Rc::downgrade(ui);
+    impossible.connect_toggled(move |item| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        for tab in &ui.tabs {
+            tab.set_show_impossible(item.is_active());
         }
+    });
+    view_menu.append(&impossible);
+
+    let count_repeats = gtk::CheckMenuItem::with_mnemonic(
+        "_Count Repeated Correct Characters");
 
-        ui.refresh();
-        Inhibit(false)
+    let ui_ptr = Rc::downgrade(ui);
+    count_repeats.connect_toggled(move |item| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        for tab in &ui.tabs {
+            tab.set_count_repeats(item.is_active());
+        }
     });
-}
+    view_menu.append(&count_repeats);
 
-fn connect_insert_text(hook_type: SetType, ui: &Rc<UI>) {
-    let entry = match hook_type {
-        SetType::Excluded => &ui.exclude,
-        SetType::Included => &ui.include
-    };
+    let answers_only = gtk::CheckMenuItem::with_mnemonic(
+        "Possible A_nswers Only");
+    answers_only.set_sensitive(ui.tabs.iter().any(|tab| tab.has_answers()));
 
-    let ui_ptr = Rc::downgrade(&ui);
-    entry.connect_insert_text(move |entry, s, _| {
-        if let Some(ch) = s.chars().next() {
-            if is_non_include_character(ch) {
-                gdk::beep();
-                signal::signal_stop_emission_by_name(entry, "insert-text");
-                return;
-            }
+    let ui_ptr = Rc::downgrade(ui);
+    answers_only.connect_toggled(move |item| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        for tab in &ui.tabs {
+            tab.set_answers_only(item.is_active());
+        }
+    });
+    view_menu.append(&answers_only);
 
-            if ch.is_ascii_alphabetic() {
-                let rc = ui_ptr.upgrade().unwrap();
-                let ui: &UI = rc.borrow();
-                ui.dictionary.add_char(hook_type, ch);
-                display_results(&ui.dictionary, &ui.results);
-            }
+    view_menu.append(&gtk::SeparatorMenuItem::new());
+    let combined = gtk::CheckMenuItem::with_mnemonic(
+        "Co_mbined Constraint Entry");
+
+    let ui_ptr = Rc::downgrade(ui);
+    combined.connect_toggled(move |item| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        for tab in &ui.tabs {
+            tab.set_combined(item.is_active());
+        }
+    });
+    view_menu.append(&combined);
+
+    let word_check = gtk::CheckMenuItem::with_mnemonic("Show _Word Check");
+
+    let ui_ptr = Rc::downgrade(ui);
+    word_check.connect_toggled(move |item| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        for tab in &ui.tabs {
+            tab.set_word_check(item.is_active());
         }
     });
+    view_menu.append(&word_check);
+
+    view_menu
 }
 
-fn position_connect_delete_text(ui: &Rc<UI>) {
-    for (pos, entry) in ui.positions.iter().enumerate() {
+fn build_columns_menu(ui: &Rc<UI>) -> gtk::Menu {
+    let menu = gtk::Menu::new();
+    let current = ui.config.borrow().result_columns;
+
+    let mut group: Option<gtk::RadioMenuItem> = None;
+    for count in RESULT_COLUMNS {
+        let label = match count {
+            1 => String::from("_1 Column"),
+            count => format!("_{} Columns", count)
+        };
+        let item = match &group {
+            Some(group) =>
+                gtk::RadioMenuItem::with_mnemonic_from_widget(group,
+                                                              Some(&label)),
+            None => gtk::RadioMenuItem::with_mnemonic(&label)
+        };
+        item.set_active(*count == current);
+
         let ui_ptr = Rc::downgrade(ui);
-        entry.connect_delete_text(move |_, _, _| {
+        let count = *count;
+        item.connect_toggled(move |item| {
+            if !item.is_active() { return; }
+
             let rc = ui_ptr.upgrade().unwrap();
             let ui: &UI = rc.borrow();
-
-            ui.dictionary.unset_char_position(pos+1);
-            display_results(&ui.dictionary, &ui.results);
+            for tab in &ui.tabs {
+                tab.set_result_columns(count);
+            }
+            ui.config.borrow_mut().result_columns = count;
+            ui.save_config();
         });
+
+        menu.append(&item);
+        group.get_or_insert(item);
     }
+    menu
 }
 
-fn position_connect_insert_text(ui: &Rc<UI>) {
-    for entry in &ui.positions {
-        let ui_ptr = Rc::downgrade(ui);
-        entry.connect_insert_text(move |entry, s, pos| {
-            if *pos > 0 { return; }
-
-            if let Some(ch) = s.chars().next() {
-                if !ch.is_ascii_alphabetic() {
-                    gdk::beep();
-                    signal::signal_stop_emission_by_name(entry, "insert-text");
-                    entry.set_text("");
-                    return;
+fn choose_results_font(ui: &UI) {
+    let window = ui.notebook.toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+
+    let dialog = gtk::FontChooserDialog::new(Some("Results Font"),
+                                             window.as_ref());
+    dialog.set_font(&ui.config.borrow().results_font);
+
+    if dialog.run() == gtk::ResponseType::Ok {
+        if let Some(font) = dialog.font() {
+            for tab in &ui.tabs {
+                tab.set_results_font(&font);
+            }
+            ui.config.borrow_mut().results_font = font.to_string();
+            ui.save_config();
+        }
+    }
+    unsafe { dialog.destroy(); }
+}
+
+fn show_boards(ui: &UI, count: usize) {
+    let window = ui.notebook.toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+    let length = ui.current_tab().map_or(DEFAULT_LENGTH, |tab| tab.length());
+
+    let board_set = BoardSet::new(&ui.word_list.borrow(), length, count);
+    if let Some(answers) = &ui.answers {
+        board_set.set_answers(answers.words());
+    }
+
+    if let Some(boards) = ui.boards.borrow_mut().take() {
+        boards.close();
+    }
+    let boards = Boards::new(board_set, window.as_ref());
+    boards.present();
+    *ui.boards.borrow_mut() = Some(boards);
+}
+
+fn show_word_difficulty(ui: &UI) {
+    let window = ui.notebook.toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Word Difficulty"),
+        window.as_ref(),
+        gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+        &[("_Close", gtk::ResponseType::Close)]);
+
+    let entry = gtk::Entry::new();
+    let result = gtk::Label::new(Some("Enter a word to rate its difficulty."));
+    let content = dialog.content_area();
+    content.set_spacing(8);
+    content.set_border_width(8);
+    content.add(&entry);
+    content.add(&result);
+
+    let tabs = ui.tabs.clone();
+    entry.connect_changed(move |entry| {
+        let word = entry.text();
+        let length = word.chars().count();
+        let text = match tabs.iter().find(|tab| tab.length() == length) {
+            Some(tab) => {
+                format!("Difficulty: {:.2}", tab.difficulty_score(&word))
+            },
+            None => String::from("No word list for words of this length.")
+        };
+        result.set_text(&text);
+    });
+
+    dialog.show_all();
+    dialog.run();
+    unsafe { dialog.destroy(); }
+}
+
+fn show_guess_comparison(ui: &UI) {
+    let tab = match ui.current_tab() {
+        Some(tab) => tab.clone(),
+        None => return
+    };
+    let window = ui.notebook.toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Compare Guesses"),
+        window.as_ref(),
+        gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+        &[("_Close", gtk::ResponseType::Close)]);
+
+    let grid = gtk::Grid::new();
+    grid.set_row_spacing(8);
+    grid.set_column_spacing(16);
+    let metrics = ["Guess", "Entropy", "Worst case", "Expected remaining"];
+    for (row, metric) in metrics.iter().enumerate() {
+        let label = gtk::Label::new(Some(metric));
+        label.set_halign(gtk::Align::Start);
+        grid.attach(&label, 0, row as i32, 1, 1);
+    }
+
+    let entries = [gtk::Entry::new(), gtk::Entry::new()];
+    let values = (0..2)
+        .map(|column| {
+            entries[column].set_max_length(tab.length() as i32);
+            grid.attach(&entries[column], column as i32 + 1, 0, 1, 1);
+            (1..metrics.len())
+                .map(|row| {
+                    let value = gtk::Label::new(None);
+                    grid.attach(&value, column as i32 + 1, row as i32, 1, 1);
+                    value
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let hint = format!("Enter two {} letter guesses to compare.",
+                       tab.length());
+    let result = gtk::Label::new(Some(&hint));
+    let content = dialog.content_area();
+    content.set_spacing(8);
+    content.set_border_width(8);
+    content.add(&grid);
+    content.add(&result);
+
+    for entry in &entries {
+        let entries = entries.clone();
+        let values = values.clone();
+        let tab = tab.clone();
+        entry.connect_changed(move |_| {
+            let a = entries[0].text();
+            let b = entries[1].text();
+            let complete = [&a, &b].iter()
+                .all(|guess| guess.chars().count() == tab.length());
+            if !complete {
+                for value in values.iter().flatten() {
+                    value.set_text("");
                 }
+                return;
+            }
 
-                let rc = ui_ptr.upgrade().unwrap();
-                let ui: &UI = rc.borrow();
-                let pos: usize = unsafe {
-                    *entry.data("index").unwrap().as_ptr()
-                };
-                ui.dictionary.set_char_position(pos+1, ch);
-                display_results(&ui.dictionary, &ui.results);
+            let comparison = tab.compare_guesses(&a, &b);
+            for (column, metrics) in [comparison.first, comparison.second]
+                .iter().enumerate()
+            {
+                values[column][0].set_text(&format!("{:.2} bits",
+                                                    metrics.entropy));
+                values[column][1].set_text(&metrics.worst_case.to_string());
+                let remaining = format!("{:.1}", metrics.expected_remaining);
+                values[column][2].set_text(&remaining);
             }
         });
     }
+
+    dialog.show_all();
+    dialog.run();
+    unsafe { dialog.destroy(); }
 }
 
-fn position_connect_focus_out_event(ui: &Rc<UI>) {
-    for entry in &ui.positions {
-        let ui_ptr = Rc::downgrade(ui);
+fn show_elimination_ranking(ui: &UI) {
+    let tab = match ui.current_tab() {
+        Some(tab) => tab,
+        None => return
+    };
+    let window = ui.notebook.toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Rank by Elimination"),
+        window.as_ref(),
+        gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+        &[("_Close", gtk::ResponseType::Close)]);
+    dialog.set_default_size(280, 400);
+
+    let ranked = tab.rank_by_elimination();
+    let total = ranked.len().saturating_sub(1);
+    let mut text = String::new();
+    for (word, eliminated) in ranked.iter().take(ELIMINATION_RANKS) {
+        text.push_str(&format!("{}  {} of {}\n", word, eliminated, total));
+    }
+
+    let label = gtk::Label::new(Some("Average number of other candidates \
+                                      each guess rules out:"));
+    label.set_line_wrap(true);
+    let results = gtk::TextView::new();
+    results.set_editable(false);
+    results.set_cursor_visible(false);
+    results.set_monospace(true);
+    results.buffer()
+        .expect("Couldn't get ranking buffer.")
+        .set_text(&text);
 
-        entry.connect_focus_out_event(move |_, _| {
+    let none = gtk::Adjustment::NONE;
+    let scrolled = gtk::ScrolledWindow::new(none, none);
+    scrolled.set_shadow_type(gtk::ShadowType::In);
+    scrolled.add(&results);
+
+    let content = dialog.content_area();
+    content.set_spacing(8);
+    content.set_border_width(8);
+    content.add(&label);
+    content.pack_start(&scrolled, true, true, 0);
+
+    dialog.show_all();
+    dialog.run();
+    unsafe { dialog.destroy(); }
+}
+
+fn show_annotated_matches(ui: &UI) {
+    let tab = match ui.current_tab() {
+        Some(tab) => tab,
+        None => return
+    };
+    let window = ui.notebook.toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Annotated Candidates"),
+        window.as_ref(),
+        gtk::DialogFlags::MODAL | gtk::DialogFlags::DESTROY_WITH_PARENT,
+        &[("_Close", gtk::ResponseType::Close)]);
+    dialog.set_default_size(320, 400);
+
+    let mut text = format!("{:<width$}  {:>10}  {:>7}\n", "word",
+                           "likelihood", "guesses",
+                           width = tab.length());
+    let annotated = tab.annotated_matches();
+    if annotated.is_empty() {
+        text.push_str("(no possible answers remain)\n");
+    }
+    for info in annotated.iter().take(ANNOTATED_MATCHES) {
+        text.push_str(&format!("{:<width$}  {:>9.1}%  {:>7.2}\n", info.word,
+                               info.likelihood * 100.0,
+                               info.expected_guesses,
+                               width = tab.length()));
+    }
+
+    let label = gtk::Label::new(Some("How likely each candidate is to be \
+                                      the answer and the estimated number \
+                                      of guesses to solve if it is guessed \
+                                      next:"));
+    label.set_line_wrap(true);
+    let results = gtk::TextView::new();
+    results.set_editable(false);
+    results.set_cursor_visible(false);
+    results.set_monospace(true);
+    results.buffer()
+        .expect("Couldn't get candidates buffer.")
+        .set_text(&text);
+
+    let none = gtk::Adjustment::NONE;
+    let scrolled = gtk::ScrolledWindow::new(none, none);
+    scrolled.set_shadow_type(gtk::ShadowType::In);
+    scrolled.add(&results);
+
+    let content = dialog.content_area();
+    content.set_spacing(8);
+    content.set_border_width(8);
+    content.add(&label);
+    content.pack_start(&scrolled, true, true, 0);
+
+    dialog.show_all();
+    dialog.run();
+    unsafe { dialog.destroy(); }
+}
+
+fn build_ui(ui: &Rc<UI>) -> gtk::Box {
+    let vbox = gtk::Box::new(gtk::Orientation::Vertical, 8);
+    let menubar = build_menubar(ui);
+    vbox.add(&menubar);
+    if let Some(info_bar) = build_skipped_info_bar(ui) {
+        vbox.add(&info_bar);
+    }
+    if let Some(info_bar) = build_warnings_info_bar(ui) {
+        vbox.add(&info_bar);
+    }
+
+    for tab in &ui.tabs {
+        let label = gtk::Label::new(Some(&format!("{} Letters", tab.length())));
+        ui.notebook.append_page(&tab.build(), Some(&label));
+    }
+    vbox.pack_start(&ui.notebook, true, true, 0);
+    vbox.add(&ui.statusbar);
+
+    return vbox;
+}
+
+fn build_skipped_info_bar(ui: &UI) -> Option<gtk::InfoBar> {
+    let skipped = ui.word_list.borrow().skipped_lengths(WORD_LENGTHS);
+    if skipped == 0 {
+        return None;
+    }
+
+    let lengths = WORD_LENGTHS.iter()
+        .map(|length| length.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let label = gtk::Label::new(Some(&format!(
+        "{} words skipped (wrong length); only {}-letter words are used.",
+        skipped, lengths)));
+    label.set_line_wrap(true);
+
+    let info_bar = gtk::InfoBar::new();
+    info_bar.set_message_type(gtk::MessageType::Info);
+    info_bar.set_show_close_button(true);
+    info_bar.content_area().add(&label);
+    info_bar.connect_response(|info_bar, _| info_bar.hide());
+    Some(info_bar)
+}
+
+fn build_warnings_info_bar(ui: &UI) -> Option<gtk::InfoBar> {
+    if ui.warnings.is_empty() {
+        return None;
+    }
+
+    let mut text = format!("{} problems reading {}; the remaining words were \
+                            loaded:", ui.warnings.len(),
+                           ui.word_list.borrow().path().display());
+    for warning in ui.warnings.iter().take(WARNINGS_SHOWN) {
+        text.push_str(&format!("\n{}", warning));
+    }
+    if ui.warnings.len() > WARNINGS_SHOWN {
+        text.push_str(&format!("\nand {} more.",
+                               ui.warnings.len() - WARNINGS_SHOWN));
+    }
+    let label = gtk::Label::new(Some(&text));
+    label.set_line_wrap(true);
+    label.set_xalign(0.0);
+
+    let info_bar = gtk::InfoBar::new();
+    info_bar.set_message_type(gtk::MessageType::Warning);
+    info_bar.set_show_close_button(true);
+    info_bar.content_area().add(&label);
+    info_bar.connect_response(|info_bar, _| info_bar.hide());
+    Some(info_bar)
+}
+
+fn application_connect_activate(ui: &Rc<UI>) {
+    let vbox = build_ui(&ui);
+    let ui_ptr = Rc::downgrade(ui);
+    ui.application.connect_activate(move |app| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        let window = gtk::ApplicationWindow::new(app);
+
+        window.set_title("Girdle");
+        window.set_border_width(8);
+        window.set_position(gtk::WindowPosition::Center);
+        window.set_keep_above(ui.config.borrow().keep_above);
+        window.add_accel_group(&ui.accel_group);
+        window.add(&vbox);
+        window.show_all();
+        window_connect_key_press_event(&rc, &window);
+        #[cfg(feature = "watch")]
+        watch_dictionary(&rc);
+
+        let page = ui.tabs.iter()
+            .position(|tab| tab.length() == DEFAULT_LENGTH);
+        ui.notebook.set_current_page(page.map(|page| page as u32));
+        notebook_connect_switch_page(&rc);
+    });
+}
+
+fn window_connect_key_press_event(ui: &Rc<UI>,
+                                  window: &gtk::ApplicationWindow)
+{
+    let ui_ptr = Rc::downgrade(ui);
+    window.connect_key_press_event(move |window, event| {
+        let modifiers = gdk::ModifierType::CONTROL_MASK |
+            gdk::ModifierType::MOD1_MASK;
+        if event.state().intersects(modifiers) {
+            return Inhibit(false);
+        }
+
+        let typing = window.focused_widget().is_some_and(|widget| {
+            widget.is::<gtk::Editable>() || widget.is::<gtk::TextView>()
+        });
+        let ch = match event.keyval().to_lower().to_unicode() {
+            Some(ch) if !typing && ch.is_alphabetic() => ch,
+            _ => return Inhibit(false)
+        };
+
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        match ui.current_tab() {
+            Some(tab) if tab.cycle_key(ch) => Inhibit(true),
+            _ => Inhibit(false)
+        }
+    });
+}
+
+fn reload_word_list(ui: &UI) -> Result<(), Error> {
+    let word_list = ui.word_list.borrow().reload()?;
+    *ui.word_list.borrow_mut() = word_list;
+    Ok(())
+}
+
+#[cfg(feature = "watch")]
+fn watch_dictionary(ui: &Rc<UI>) {
+    if ui.word_list.borrow().is_embedded() {
+        return;
+    }
+
+    let file = gio::File::for_path(ui.word_list.borrow().path());
+    let monitor = match file.monitor_file(gio::FileMonitorFlags::NONE,
+                                          gio::Cancellable::NONE)
+    {
+        Ok(monitor) => monitor,
+        Err(error) => {
+            let context_id = ui.statusbar.context_id("watch");
+            let msg = format!("Unable to watch {}: {}",
+                              ui.word_list.borrow().path().display(),
+                              error);
+            ui.statusbar.push(context_id, &msg);
+            return;
+        }
+    };
+
+    let ui_ptr = Rc::downgrade(ui);
+    monitor.connect_changed(move |_, _, _, event| {
+        match event {
+            gio::FileMonitorEvent::Changed |
+            gio::FileMonitorEvent::ChangesDoneHint |
+            gio::FileMonitorEvent::Created => (),
+            _ => return
+        }
+
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+        if let Some(source) = ui.pending_reload.borrow_mut().take() {
+            source.remove();
+        }
+
+        let ui_ptr = ui_ptr.clone();
+        let source = glib::timeout_add_local(RELOAD_DELAY, move || {
             let rc = ui_ptr.upgrade().unwrap();
             let ui: &UI = rc.borrow();
+            ui.pending_reload.borrow_mut().take();
 
-            ui.refresh();
-            Inhibit(false)
+            // Each tab reports its own reload failure in the status bar.
+            let _ = reload_word_list(ui);
+            for tab in &ui.tabs {
+                let _ = tab.reload();
+            }
+            Continue(false)
         });
-    }
+        *ui.pending_reload.borrow_mut() = Some(source);
+    });
+    *ui.monitor.borrow_mut() = Some(monitor);
+}
+
+fn notebook_connect_switch_page(ui: &Rc<UI>) {
+    let ui_ptr = Rc::downgrade(ui);
+    ui.notebook.connect_switch_page(move |_, _, page| {
+        let rc = ui_ptr.upgrade().unwrap();
+        let ui: &UI = rc.borrow();
+
+        if let Some(tab) = ui.tabs.get(page as usize) {
+            tab.status(&format!("switched to {}-letter words",
+                                tab.length()));
+        }
+    });
 }
@@ -0,0 +1,216 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{self,Command};
+
+use gtk::gdk_pixbuf::Pixbuf;
+
+use girdle::dictionary::Error;
+use girdle::dictionary::Feedback;
+use girdle::dictionary::LetterState;
+use girdle::dictionary::Result;
+
+const TESSERACT: &str = "tesseract";
+const MAX_DISTANCE: i32 = 40 * 40 * 3;
+
+// Light, dark and high contrast tile colors.
+const PALETTE: &[((i32, i32, i32), LetterState)] = &[
+    ((0x6a, 0xaa, 0x64), LetterState::Correct),
+    ((0x53, 0x8d, 0x4e), LetterState::Correct),
+    ((0xf5, 0x79, 0x3a), LetterState::Correct),
+    ((0xc9, 0xb4, 0x58), LetterState::Present),
+    ((0xb5, 0x9f, 0x3b), LetterState::Present),
+    ((0x85, 0xc0, 0xf9), LetterState::Present),
+    ((0x78, 0x7c, 0x7e), LetterState::Absent),
+    ((0x3a, 0x3a, 0x3c), LetterState::Absent)
+];
+
+struct Image
+{
+    pixbuf: Pixbuf,
+    width: usize,
+    height: usize,
+    states: Vec<Option<LetterState>>
+}
+
+struct Tile
+{
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize
+}
+
+pub fn read_rows<P: AsRef<Path>>(path: P, length: usize)
+    -> Result<Vec<(String, Feedback)>>
+{
+    let image = Image::load(path.as_ref())?;
+    let mut rows = Vec::new();
+
+    for (top, bottom) in image.bands() {
+        let tiles = image.tiles(top, bottom);
+        if tiles.len() != length {
+            continue;
+        }
+
+        let mut guess = String::new();
+        let mut states = Vec::new();
+        for tile in &tiles {
+            guess.push(image.letter(tile)?);
+            states.push(image.state(tile)?);
+        }
+        rows.push((guess, Feedback::new(states)));
+    }
+
+    if rows.is_empty() {
+        let msg = format!("No rows of {} tiles found in {}.", length,
+                          path.as_ref().display());
+        return Err(Error::new(&msg));
+    }
+    Ok(rows)
+}
+
+fn classify(r: u8, g: u8, b: u8) -> Option<LetterState> {
+    PALETTE.iter()
+        .map(|((pr, pg, pb), state)| {
+            let (dr, dg, db) = (r as i32 - pr, g as i32 - pg, b as i32 - pb);
+            (dr * dr + dg * dg + db * db, *state)
+        })
+        .filter(|(distance, _)| *distance <= MAX_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, state)| state)
+}
+
+fn segments(counts: &[usize], threshold: usize) -> Vec<(usize, usize)> {
+    let mut segments = Vec::new();
+    let mut start = None;
+    for (i, count) in counts.iter().enumerate() {
+        match (start, *count >= threshold) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                segments.push((s, i));
+                start = None;
+            },
+            _ => ()
+        }
+    }
+    if let Some(s) = start {
+        segments.push((s, counts.len()));
+    }
+    segments
+}
+
+fn to_error(error: glib::Error) -> Error {
+    Error::new(&format!("{}", error))
+}
+
+impl Image {
+    fn load(path: &Path) -> Result<Image> {
+        let pixbuf = Pixbuf::from_file(path).map_err(to_error)?;
+        let bytes = pixbuf.read_pixel_bytes()
+            .ok_or_else(|| Error::new("Unable to read the image pixels."))?;
+
+        let width = pixbuf.width() as usize;
+        let height = pixbuf.height() as usize;
+        let channels = pixbuf.n_channels() as usize;
+        let stride = pixbuf.rowstride() as usize;
+
+        let mut states = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * stride + x * channels;
+                states.push(classify(bytes[i], bytes[i + 1], bytes[i + 2]));
+            }
+        }
+
+        Ok(Image { pixbuf, width, height, states })
+    }
+
+    fn is_tile(&self, x: usize, y: usize) -> bool {
+        self.states[y * self.width + x].is_some()
+    }
+
+    fn bands(&self) -> Vec<(usize, usize)> {
+        let counts = (0..self.height)
+            .map(|y| (0..self.width).filter(|x| self.is_tile(*x, y)).count())
+            .collect::<Vec<_>>();
+        segments(&counts, self.width / 20 + 1)
+    }
+
+    fn tiles(&self, top: usize, bottom: usize) -> Vec<Tile> {
+        let height = bottom - top;
+        let counts = (0..self.width)
+            .map(|x| (top..bottom).filter(|y| self.is_tile(x, *y)).count())
+            .collect::<Vec<_>>();
+
+        segments(&counts, height / 2 + 1).into_iter()
+            .map(|(left, right)| Tile {
+                x: left,
+                y: top,
+                width: right - left,
+                height
+            })
+            .filter(|tile| {
+                let ratio = tile.width as f64 / tile.height as f64;
+                (0.85..=1.18).contains(&ratio)
+            })
+            .collect()
+    }
+
+    fn state(&self, tile: &Tile) -> Result<LetterState> {
+        let mut counts = [0usize; 3];
+        for y in tile.y..tile.y + tile.height {
+            for x in tile.x..tile.x + tile.width {
+                match self.states[y * self.width + x] {
+                    Some(LetterState::Correct) => counts[0] += 1,
+                    Some(LetterState::Present) => counts[1] += 1,
+                    Some(LetterState::Absent) => counts[2] += 1,
+                    None => ()
+                }
+            }
+        }
+
+        let states = [LetterState::Correct, LetterState::Present,
+                      LetterState::Absent];
+        states.into_iter().zip(counts)
+            .max_by_key(|(_, count)| *count)
+            .map(|(state, _)| state)
+            .ok_or_else(|| Error::new("Unable to classify a tile color."))
+    }
+
+    fn letter(&self, tile: &Tile) -> Result<char> {
+        let cell = self.pixbuf
+            .new_subpixbuf(tile.x as i32, tile.y as i32,
+                           tile.width as i32, tile.height as i32)
+            .ok_or_else(|| Error::new("Unable to crop a tile."))?;
+
+        let path = env::temp_dir().join(format!("girdle-ocr-{}-{}-{}.png",
+                                                process::id(), tile.x,
+                                                tile.y));
+        cell.savev(&path, "png", &[]).map_err(to_error)?;
+
+        let output = Command::new(TESSERACT)
+            .arg(&path)
+            .args(["stdout", "--psm", "10"])
+            .args(["-c", "tessedit_char_whitelist=ABCDEFGHIJKLMNOPQRSTUVWXYZ"])
+            .output();
+        let _ = fs::remove_file(&path);
+
+        let output = output.map_err(|error| {
+            let msg = format!("Unable to run {}: {}", TESSERACT, error);
+            Error::new(&msg)
+        })?;
+        String::from_utf8_lossy(&output.stdout).chars()
+            .find(|ch| ch.is_alphabetic())
+            .map(|ch| ch.to_ascii_lowercase())
+            .ok_or_else(|| {
+                let msg = format!("Unable to read the letter at {}, {}.",
+                                  tile.x, tile.y);
+                Error::new(&msg)
+            })
+    }
+}
@@ -0,0 +1,2110 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::borrow::Borrow;
+use std::cell::{Ref,RefCell};
+use std::collections::{HashMap,HashSet};
+use std::rc::{Rc,Weak};
+use std::time::Duration;
+
+use gdk;
+use glib::signal;
+use glib::SignalHandlerId;
+use glib::SourceId;
+use gtk::atk::prelude::*;
+use gtk::pango;
+use gtk::prelude::*;
+
+use girdle::Dictionary;
+use girdle::dictionary::Constraint;
+use girdle::dictionary::Error;
+use girdle::dictionary::Feedback;
+use girdle::dictionary::GuessComparison;
+use girdle::dictionary::LetterState;
+use girdle::dictionary::MatchInfo;
+use girdle::dictionary::SetType;
+use girdle::dictionary::SortMode;
+use girdle::dictionary::display_width;
+
+#[cfg(feature = "ocr")]
+use crate::gtk::ocr;
+
+const RESULTS_PER_PAGE: usize = 200;
+const STREAM_BATCH: usize = 2000;
+const COLUMN_GAP: &str = "  ";
+const USEFUL_LETTERS: usize = 10;
+const POSITION_LETTERS: i32 = 27;
+const POSITION_CSS: &str = "\
+    entry.locked { font-weight: bold; color: #4e9a06; }
+    entry.excluded { font-style: italic; color: #a40000; }";
+const COMBINED_TAGS: &[(LetterState, &str, &str)] = &[
+    (LetterState::Absent, "absent", "#787c7e"),
+    (LetterState::Present, "present", "#c9b458"),
+    (LetterState::Correct, "correct", "#6aaa64")
+];
+const KEYBOARD_ROWS: &[&str] = &["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+const CONSTRAINTS: &[(Constraint, &str)] = &[
+    (Constraint::Included, "Correct"),
+    (Constraint::Excluded, "Incorrect"),
+    (Constraint::Positions, "Positions"),
+    (Constraint::Misplaced, "Misplaced"),
+    (Constraint::Counts, "Counts")
+];
+
+struct SignalIds {
+    exclude: RefCell<Option<SignalHandlerId>>,
+    include: RefCell<Option<SignalHandlerId>>
+}
+
+#[derive(Clone,Copy)]
+enum Mark
+{
+    Excluded(char),
+    Misplaced(usize, char),
+    Position(usize, char),
+    NotAt(usize, char)
+}
+
+pub struct Tab {
+    this: Weak<Tab>,
+    dictionary: Dictionary,
+    signal_ids: SignalIds,
+    insert_signal_ids: SignalIds,
+    include: gtk::Entry,
+    exclude: gtk::Entry,
+    separate: gtk::Box,
+    combined: gtk::TextView,
+    combined_box: gtk::Box,
+    marks: RefCell<Vec<Mark>>,
+    results: gtk::TextView,
+    results_label: gtk::Label,
+    search: gtk::SearchEntry,
+    results_css: gtk::CssProvider,
+    stats: gtk::Label,
+    page: RefCell<usize>,
+    columns: RefCell<usize>,
+    stream: RefCell<Option<SourceId>>,
+    pending_status: RefCell<Option<String>>,
+    inverted: RefCell<bool>,
+    answers_only: RefCell<bool>,
+    count_repeats: RefCell<bool>,
+    accumulate: RefCell<bool>,
+    show_impossible: RefCell<bool>,
+    page_label: gtk::Label,
+    previous: gtk::Button,
+    next: gtk::Button,
+    history: gtk::ListBox,
+    useful: gtk::Label,
+    statusbar: gtk::Statusbar,
+    positions: Vec<gtk::Entry>,
+    position_signals: RefCell<Vec<SignalHandlerId>>,
+    position_insert_signals: RefCell<Vec<SignalHandlerId>>,
+    suggested: RefCell<Vec<usize>>,
+    clear_positions: gtk::Button,
+    locked: RefCell<Vec<bool>>,
+    guess: gtk::Entry,
+    feedback: gtk::Entry,
+    apply: gtk::Button,
+    check: gtk::Entry,
+    check_box: gtk::Box,
+    constraints: Vec<(Constraint, gtk::CheckButton)>,
+    keyboard: Vec<(char, gtk::Button)>,
+    untried: gtk::Label
+}
+
+impl SignalIds {
+    pub fn new() -> SignalIds {
+        SignalIds {
+            exclude: RefCell::new(None),
+            include: RefCell::new(None)
+        }
+    }
+
+    pub fn signal(&self, hook_type: SetType) -> Ref<SignalHandlerId> {
+        match hook_type {
+            SetType::Excluded => Ref::map(self.exclude.borrow(), |v|
+                                          v.as_ref().unwrap()),
+            SetType::Included => Ref::map(self.include.borrow(), |v|
+                                          v.as_ref().unwrap())
+        }
+    }
+}
+
+impl Tab {
+    pub fn new(dictionary: Dictionary, statusbar: &gtk::Statusbar) -> Rc<Tab> {
+        let tab = new_tab(dictionary, statusbar);
+        let include = connect_delete_text(SetType::Included, &tab);
+        let exclude = connect_delete_text(SetType::Excluded, &tab);
+        tab.set_signal_ids(include, exclude);
+
+        connect_focus_out_event(SetType::Included, &tab);
+        let include = connect_insert_text(SetType::Included, &tab);
+
+        connect_focus_out_event(SetType::Excluded, &tab);
+        let exclude = connect_insert_text(SetType::Excluded, &tab);
+        tab.set_insert_signal_ids(include, exclude);
+
+        position_connect_delete_text(&tab);
+        position_connect_focus_out_event(&tab);
+        position_connect_insert_text(&tab);
+        position_connect_button_press_event(&tab);
+        clear_positions_connect_clicked(&tab);
+        results_connect_button_press_event(&tab);
+        combined_connect_changed(&tab);
+        combined_connect_button_press_event(&tab);
+        search_connect_search_changed(&tab);
+        guess_connect_activate(&tab);
+        check_connect_changed(&tab);
+        page_connect_clicked(&tab);
+        constraint_connect_toggled(&tab);
+        keyboard_connect_clicked(&tab);
+        tab.refresh_keyboard();
+        tab
+    }
+
+    pub fn length(&self) -> usize {
+        self.dictionary.length()
+    }
+
+    pub fn reset(&self) {
+        self.dictionary.reset();
+        self.clear();
+        self.status("reset all constraints");
+    }
+
+    pub fn reload(&self) -> Result<(), Error> {
+        match self.dictionary.reload() {
+            Ok(()) => {
+                self.refresh_keyboard();
+                self.display_results();
+                self.status(&format!("reloaded {} words",
+                                     self.dictionary.word_count()));
+                Ok(())
+            },
+
+            Err(error) => {
+                self.status(&format!("unable to reload: {}", error));
+                Err(error)
+            }
+        }
+    }
+
+    pub fn status(&self, action: &str) {
+        let context_id = self.statusbar.context_id("action");
+        self.statusbar.remove_all(context_id);
+
+        if self.stream.borrow().is_some() {
+            *self.pending_status.borrow_mut() = Some(String::from(action));
+            self.statusbar.push(context_id,
+                                &format!("filtering: {}", action));
+            return;
+        }
+
+        let count = self.dictionary.remaining_count();
+        match self.dictionary.contradiction() {
+            Some(reason) if count == 0 => {
+                let msg = format!("constraints are contradictory: {}",
+                                  reason);
+                self.statusbar.push(context_id, &msg);
+            },
+            _ => {
+                self.statusbar.push(context_id,
+                                    &format!("{} matches: {}", count, action));
+            }
+        }
+        self.results_label
+            .set_tooltip_text(Some(&self.dictionary.describe()));
+        self.refresh_stats(count);
+        self.refresh_position_hints();
+        self.refresh_useful_letters();
+        self.refresh_word_check();
+        self.refresh_impossible_letters();
+    }
+
+    pub fn set_sort_mode(&self, sort_mode: SortMode) {
+        self.dictionary.set_sort_mode(sort_mode);
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        if buffer.char_count() > 0 {
+            self.display_results();
+        }
+    }
+
+    pub fn result_columns(&self) -> usize {
+        *self.columns.borrow()
+    }
+
+    pub fn set_result_columns(&self, columns: usize) {
+        *self.columns.borrow_mut() = columns.max(1);
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        if buffer.char_count() > 0 {
+            self.display_page();
+        }
+    }
+
+    pub fn set_keep_greens(&self, keep_greens: bool) {
+        self.dictionary.set_keep_greens(keep_greens);
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        if buffer.char_count() > 0 {
+            self.display_results();
+        }
+    }
+
+    pub fn set_accumulate_excludes(&self, accumulate: bool) {
+        *self.accumulate.borrow_mut() = accumulate;
+        self.refresh();
+    }
+
+    pub fn clear_excludes(&self) {
+        self.dictionary.clear_set(SetType::Excluded);
+        self.refresh();
+        self.display_results();
+        self.status("cleared excluded characters");
+    }
+
+    // Letters already known to be in the word are left alone.
+    pub fn exclude_letters(&self, word: &str) {
+        let included = self.dictionary.included_chars();
+        let known = (1..=self.length())
+            .filter_map(|pos| self.dictionary.char_position(pos))
+            .collect::<Vec<_>>();
+        for ch in word.chars() {
+            if !included.contains(&ch) && !known.contains(&ch) {
+                self.dictionary.add_char(SetType::Excluded, ch);
+            }
+        }
+
+        self.refresh();
+        self.display_results();
+        self.status(&format!("excluded the letters of '{}'", word));
+    }
+
+    pub fn set_show_impossible(&self, show_impossible: bool) {
+        *self.show_impossible.borrow_mut() = show_impossible;
+        self.refresh_impossible_letters();
+    }
+
+    pub fn set_hide_guessed(&self, hide_guessed: bool) {
+        self.dictionary.set_hide_guessed(hide_guessed);
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        if buffer.char_count() > 0 {
+            self.display_results();
+        }
+    }
+
+    pub fn set_count_repeats(&self, count_repeats: bool) {
+        *self.count_repeats.borrow_mut() = count_repeats;
+
+        let text = self.include.text();
+        match count_repeats {
+            true => self.sync_include_counts(&text),
+            false => self.dictionary.clear_min_counts()
+        }
+        self.refresh();
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        if buffer.char_count() > 0 {
+            self.display_results();
+        }
+    }
+
+    pub fn has_answers(&self) -> bool {
+        self.dictionary.has_answers()
+    }
+
+    pub fn set_answers_only(&self, answers_only: bool) {
+        *self.answers_only.borrow_mut() = answers_only;
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        if buffer.char_count() > 0 {
+            self.display_results();
+        }
+    }
+
+    pub fn set_results_font(&self, font: &str) {
+        let font = pango::FontDescription::from_string(font);
+        let mut css = String::from("textview {");
+        if let Some(family) = font.family() {
+            css.push_str(&format!(" font-family: \"{}\";", family));
+        }
+        if font.size() > 0 {
+            css.push_str(&format!(" font-size: {}pt;",
+                                  font.size() / pango::SCALE));
+        }
+        css.push_str(" }");
+
+        if let Err(error) = self.results_css.load_from_data(css.as_bytes()) {
+            self.status(&format!("unable to set the results font: {}",
+                                 error));
+        }
+    }
+
+    pub fn set_inverted(&self, inverted: bool) {
+        *self.inverted.borrow_mut() = inverted;
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        if buffer.char_count() > 0 || inverted {
+            self.display_results();
+        }
+    }
+
+    pub fn build(&self) -> gtk::Box {
+        let vbox = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        vbox.set_border_width(8);
+
+        let characters = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        let include = build_character_entry(
+            "_Correct Characters", &self.include,
+            "Letters that appear somewhere in the word");
+        characters.pack_start(&include, true, true, 0);
+        let exclude = build_character_entry(
+            "_Incorrect Characters", &self.exclude,
+            "Letters that do not appear in the word");
+        characters.pack_start(&exclude, true, true, 0);
+        characters.set_focus_chain(&[include.upcast(), exclude.upcast()]);
+        self.separate.add(&characters);
+
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        let label = gtk::Label::with_mnemonic("_Exact Positions");
+        label.set_mnemonic_widget(self.positions.first());
+        hbox.add(&label);
+        self.separate.add(&hbox);
+
+        let positions = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        for (i, entry) in self.positions.iter().enumerate() {
+            set_accessible(entry, &format!("Position {}", i + 1),
+                           &format!("Letter known at position {}; prefix it \
+                                     with '!' to exclude it instead", i + 1));
+            positions.pack_start(entry, true, false, 0);
+        }
+        set_accessible(&self.clear_positions, "Clear positions",
+                       "Clear every exact position without touching the \
+                        correct and incorrect characters");
+        positions.pack_end(&self.clear_positions, false, false, 0);
+        positions.set_focus_chain(&self.positions.iter()
+                                  .map(|entry| entry.clone().upcast())
+                                  .chain(std::iter::once(
+                                      self.clear_positions.clone().upcast()))
+                                  .collect::<Vec<_>>());
+        self.separate.add(&positions);
+        self.separate.set_focus_chain(&[characters.upcast(),
+                                        positions.upcast()]);
+        vbox.add(&self.separate);
+
+        let legend = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        let label = gtk::Label::with_mnemonic("C_onstraints");
+        label.set_mnemonic_widget(Some(&self.combined));
+        legend.add(&label);
+        for (state, name, color) in COMBINED_TAGS {
+            let label = gtk::Label::new(None);
+            label.set_markup(&format!("<span background=\"{}\" \
+                                       foreground=\"white\"> {} </span> {}",
+                                      color, state.to_char(), name));
+            legend.pack_end(&label, false, false, 0);
+        }
+        self.combined_box.add(&legend);
+
+        set_accessible(&self.combined, "Constraints",
+                       "One guess per line; click a letter to cycle it \
+                        between gray, yellow and green");
+        let frame = gtk::Frame::new(None);
+        frame.set_shadow_type(gtk::ShadowType::In);
+        frame.add(&self.combined);
+        self.combined_box.add(&frame);
+        self.combined_box.set_no_show_all(true);
+        vbox.add(&self.combined_box);
+
+        let guess = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        let label = gtk::Label::with_mnemonic("G_uess");
+        label.set_mnemonic_widget(Some(&self.guess));
+        set_accessible(&self.guess, "Guess", "The word that was guessed");
+        guess.add(&label);
+        guess.pack_start(&self.guess, true, true, 0);
+
+        let label = gtk::Label::with_mnemonic("Fee_dback");
+        label.set_mnemonic_widget(Some(&self.feedback));
+        set_accessible(&self.feedback, "Feedback",
+                       "The colours returned for the guess: G for green, \
+                        Y for yellow and X for gray");
+        guess.add(&label);
+        guess.pack_start(&self.feedback, true, true, 0);
+        guess.add(&self.apply);
+        vbox.add(&guess);
+
+        let label = gtk::Label::with_mnemonic("Chec_k Word");
+        label.set_mnemonic_widget(Some(&self.check));
+        set_accessible(&self.check, "Check word",
+                       "Shows whether a word is in the dictionary and still \
+                        matches the constraints");
+        self.check_box.add(&label);
+        self.check_box.pack_start(&self.check, true, true, 0);
+        self.check_box.set_no_show_all(true);
+        vbox.add(&self.check_box);
+
+        let constraints = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        constraints.add(&gtk::Label::new(Some("Apply")));
+        for (_, button) in &self.constraints {
+            constraints.add(button);
+        }
+        vbox.add(&constraints);
+
+        let keyboard = gtk::Box::new(gtk::Orientation::Vertical, 4);
+        let mut keys = self.keyboard.iter();
+        let mut rows = KEYBOARD_ROWS.iter()
+            .map(|row| row.len()).collect::<Vec<_>>();
+        rows.push(self.keyboard.len() - rows.iter().sum::<usize>());
+        for row in rows {
+            let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+            hbox.set_halign(gtk::Align::Center);
+            for (_, button) in keys.by_ref().take(row) {
+                hbox.add(button);
+            }
+            keyboard.add(&hbox);
+        }
+        keyboard.add(&self.untried);
+        vbox.add(&keyboard);
+
+        let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        hbox.add(&self.results_label);
+        set_accessible(&self.search, "Filter results",
+                       "Only show results containing this text");
+        hbox.add(&self.search);
+        hbox.pack_end(&self.stats, false, false, 0);
+        vbox.add(&hbox);
+
+        let panes = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        let none = gtk::Adjustment::NONE;
+        let window = gtk::ScrolledWindow::new(none, none);
+        window.set_shadow_type(gtk::ShadowType::In);
+        window.add(&self.results);
+
+        let pages = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        pages.add(&self.previous);
+        pages.set_center_widget(Some(&self.page_label));
+        pages.pack_end(&self.next, false, false, 0);
+
+        let column = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        column.pack_start(&window, true, true, 0);
+        column.add(&pages);
+
+        let history = gtk::Frame::new(Some("History"));
+        let scrolled = gtk::ScrolledWindow::new(none, none);
+        scrolled.set_policy(gtk::PolicyType::Never,
+                            gtk::PolicyType::Automatic);
+        scrolled.add(&self.history);
+        history.add(&scrolled);
+
+        let useful = gtk::Frame::new(Some("Useful Letters"));
+        self.useful.set_valign(gtk::Align::Start);
+        self.useful.set_justify(gtk::Justification::Left);
+        let scrolled = gtk::ScrolledWindow::new(none, none);
+        scrolled.set_policy(gtk::PolicyType::Never,
+                            gtk::PolicyType::Automatic);
+        scrolled.add(&self.useful);
+        useful.add(&scrolled);
+
+        let side = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        side.pack_start(&history, true, true, 0);
+        side.pack_start(&useful, true, true, 0);
+        panes.pack_start(&column, true, true, 0);
+        panes.pack_start(&side, false, false, 0);
+        vbox.pack_start(&panes, true, true, 0);
+
+        vbox.set_focus_chain(&[self.separate.clone().upcast(),
+                               self.combined_box.clone().upcast(),
+                               guess.upcast(),
+                               self.check_box.clone().upcast(),
+                               panes.upcast()]);
+        vbox
+    }
+
+    pub fn sync(&self) {
+        self.refresh();
+        for (i, entry) in self.positions.iter().enumerate() {
+            if let Some(ch) = self.dictionary.char_position(i+1) {
+                entry.set_text(&ch.to_string());
+            }
+        }
+        self.display_results();
+        self.status("loaded constraints from the command line");
+    }
+
+    pub fn set_combined(&self, combined: bool) {
+        self.separate.set_no_show_all(combined);
+        self.combined_box.set_no_show_all(!combined);
+        if combined {
+            self.separate.hide();
+            self.combined_box.show_all();
+            self.combined.grab_focus();
+        } else {
+            self.combined_box.hide();
+            self.separate.show_all();
+            for (i, entry) in self.positions.iter().enumerate() {
+                let ch = self.dictionary.char_position(i+1);
+                entry.set_text(&ch.map(String::from).unwrap_or_default());
+            }
+        }
+    }
+
+    pub fn set_word_check(&self, shown: bool) {
+        self.check_box.set_no_show_all(!shown);
+        if shown {
+            self.check_box.show_all();
+            self.refresh_word_check();
+        } else {
+            self.check_box.hide();
+        }
+    }
+
+    pub fn clear_positions(&self) {
+        for (i, entry) in self.positions.iter().enumerate() {
+            if let (true, letters) = parse_position(&entry.text()) {
+                for ch in letters {
+                    self.dictionary.allow_at_position(i+1, ch);
+                }
+            }
+        }
+        self.dictionary.clear_positions();
+
+        let signals = self.position_signals.borrow();
+        for (i, (entry, signal_id)) in self.positions.iter()
+            .zip(signals.iter()).enumerate()
+        {
+            self.set_locked(i, false);
+            entry.block_signal(signal_id);
+            entry.set_text("");
+            entry.unblock_signal(signal_id);
+            entry.style_context().remove_class("excluded");
+        }
+        drop(signals);
+
+        self.display_results();
+        self.status("cleared the exact positions");
+    }
+
+    pub fn cycle_key(&self, ch: char) -> bool {
+        if !self.keyboard.iter().any(|(key, _)| *key == ch) {
+            return false;
+        }
+
+        let action = if self.dictionary.excluded_chars().contains(&ch) {
+            self.dictionary.remove_char(SetType::Excluded, ch);
+            format!("removed '{}' from {}", ch, set_name(SetType::Excluded))
+        } else {
+            self.dictionary.add_char(SetType::Excluded, ch);
+            format!("added '{}' to {}", ch, set_name(SetType::Excluded))
+        };
+
+        self.refresh();
+        self.display_results();
+        self.status(&action);
+        true
+    }
+
+    pub fn focus_include(&self) {
+        self.include.grab_focus();
+    }
+
+    pub fn use_suggestion(&self) {
+        let suggestion = match self.dictionary.suggest_guess() {
+            Some(suggestion) => suggestion,
+            None => {
+                gdk::beep();
+                self.status("no suggestion available");
+                return;
+            }
+        };
+
+        self.show_suggestion(&suggestion);
+        self.status(&format!("suggested '{}' ({:.2} bits)", suggestion,
+                             self.dictionary
+                                 .expected_information(&suggestion)));
+    }
+
+    pub fn use_frequency_suggestion(&self) {
+        let suggestion = match self.dictionary.suggest() {
+            Some(suggestion) => suggestion,
+            None => {
+                gdk::beep();
+                self.status("no suggestion available");
+                return;
+            }
+        };
+
+        self.show_suggestion(&suggestion);
+        self.status(&format!("suggested '{}' by letter frequency",
+                             suggestion));
+    }
+
+    // The suggestion is only written into the entries. Their handlers are
+    // blocked so nothing is added to the dictionary until the guess is
+    // applied or the user edits an entry.
+    fn show_suggestion(&self, suggestion: &str) {
+        self.clear_suggestion();
+
+        let delete = self.position_signals.borrow();
+        let insert = self.position_insert_signals.borrow();
+        let mut suggested = self.suggested.borrow_mut();
+        for (i, ch) in suggestion.chars().enumerate() {
+            let entry = &self.positions[i];
+            if self.is_locked(i) || !entry.text().is_empty() {
+                continue;
+            }
+
+            entry.block_signal(&delete[i]);
+            entry.block_signal(&insert[i]);
+            entry.set_text(&ch.to_string());
+            entry.unblock_signal(&insert[i]);
+            entry.unblock_signal(&delete[i]);
+            suggested.push(i);
+        }
+
+        let mut included = self.include.text().to_string();
+        for ch in suggestion.chars() {
+            if !included.contains(ch) {
+                included.push(ch);
+            }
+        }
+
+        let signal_id = self.signal_ids.signal(SetType::Included);
+        let insert_id = self.insert_signal_ids.signal(SetType::Included);
+        self.include.block_signal(&signal_id);
+        self.include.block_signal(&insert_id);
+        self.include.set_text(&included);
+        self.include.unblock_signal(&insert_id);
+        self.include.unblock_signal(&signal_id);
+
+        self.guess.set_text(suggestion);
+        self.feedback.grab_focus();
+    }
+
+    pub fn use_as_guess(&self, word: &str) {
+        self.guess.set_text(word);
+        self.feedback.grab_focus();
+        self.status(&format!("using '{}' as the next guess", word));
+    }
+
+    pub fn copy_word(&self, word: &str) {
+        let clipboard = gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+        clipboard.set_text(word);
+        self.status(&format!("copied '{}' to the clipboard", word));
+    }
+
+    // Entries the user has since edited are left alone.
+    fn clear_suggestion(&self) {
+        let delete = self.position_signals.borrow();
+        for i in self.suggested.borrow_mut().drain(..) {
+            let entry = &self.positions[i];
+            if self.dictionary.char_position(i+1).is_some() ||
+                entry.text().chars().count() != 1
+            {
+                continue;
+            }
+
+            entry.block_signal(&delete[i]);
+            entry.set_text("");
+            entry.unblock_signal(&delete[i]);
+        }
+    }
+
+    pub fn copy_emoji_grid(&self) {
+        let grid = self.dictionary.export_emoji_grid();
+        if grid.is_empty() {
+            self.status("no guesses to copy");
+            return;
+        }
+
+        let clipboard = gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+        clipboard.set_text(&grid);
+        self.status("copied the emoji grid to the clipboard");
+    }
+
+    pub fn copy_markdown_table(&self) {
+        let table = self.dictionary.export_markdown();
+        if table.is_empty() {
+            self.status("no guesses to copy");
+            return;
+        }
+
+        let clipboard = gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+        clipboard.set_text(&table);
+        self.status("copied the Markdown table to the clipboard");
+    }
+
+    pub fn difficulty_score(&self, word: &str) -> f64 {
+        self.dictionary.difficulty_score(word)
+    }
+
+    pub fn compare_guesses(&self, a: &str, b: &str) -> GuessComparison {
+        self.dictionary.compare_guesses(a, b)
+    }
+
+    pub fn annotated_matches(&self) -> Vec<MatchInfo> {
+        self.dictionary.annotated_matches()
+    }
+
+    pub fn rank_by_elimination(&self) -> Vec<(String, usize)> {
+        self.dictionary.rank_by_elimination()
+    }
+
+    pub fn save_candidates(&self, parent: Option<&gtk::Window>) {
+        let dialog = gtk::FileChooserDialog::with_buttons(
+            Some("Save Candidates as Dictionary"),
+            parent,
+            gtk::FileChooserAction::Save,
+            &[("_Cancel", gtk::ResponseType::Cancel),
+              ("_Save", gtk::ResponseType::Accept)]);
+        dialog.set_do_overwrite_confirmation(true);
+        dialog.set_current_name(&format!("candidates-{}.txt", self.length()));
+
+        if dialog.run() == gtk::ResponseType::Accept {
+            if let Some(path) = dialog.filename() {
+                match self.dictionary.save_matches(&path) {
+                    Ok(count) => {
+                        self.status(&format!("saved {} candidates to {}",
+                                             count, path.display()));
+                    },
+
+                    Err(error) => {
+                        self.status(&format!("unable to save candidates: {}",
+                                             error));
+                    }
+                }
+            }
+        }
+        unsafe { dialog.destroy(); }
+    }
+
+    fn apply_guess(&self) {
+        let guess = self.guess.text().to_lowercase();
+        let feedback = match Feedback::parse(&self.feedback.text()) {
+            Ok(feedback) => feedback,
+            Err(error) => {
+                gdk::beep();
+                self.status(&format!("{}", error));
+                return;
+            }
+        };
+
+        if let Err(error) = self.record_guess(&guess, &feedback) {
+            gdk::beep();
+            self.status(&format!("{}", error));
+            return;
+        }
+
+        self.refresh();
+        self.refresh_history();
+        self.clear_suggestion();
+        self.guess.set_text("");
+        self.feedback.set_text("");
+        self.display_results();
+
+        let mut action = format!("applied '{}' as {}", guess, feedback);
+        if !self.dictionary.contains_word(&guess) {
+            action.push_str(" (not in the dictionary)");
+        }
+        self.status(&action);
+    }
+
+    #[cfg(feature = "ocr")]
+    pub fn import_screenshot(&self, parent: Option<&gtk::Window>) {
+        let dialog = gtk::FileChooserDialog::with_buttons(
+            Some("Import Screenshot"),
+            parent,
+            gtk::FileChooserAction::Open,
+            &[("_Cancel", gtk::ResponseType::Cancel),
+              ("_Open", gtk::ResponseType::Accept)]);
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Images"));
+        filter.add_pixbuf_formats();
+        dialog.add_filter(&filter);
+
+        let path = match dialog.run() {
+            gtk::ResponseType::Accept => dialog.filename(),
+            _ => None
+        };
+        unsafe { dialog.destroy(); }
+
+        let path = match path {
+            Some(path) => path,
+            None => return
+        };
+
+        let rows = match ocr::read_rows(&path, self.length()) {
+            Ok(rows) => rows,
+            Err(error) => {
+                gdk::beep();
+                self.status(&format!("unable to import {}: {}",
+                                     path.display(), error));
+                return;
+            }
+        };
+
+        for (guess, feedback) in &rows {
+            if let Err(error) = self.record_guess(guess, feedback) {
+                gdk::beep();
+                self.status(&format!("{}", error));
+                return;
+            }
+        }
+
+        self.refresh();
+        self.refresh_history();
+        self.clear_suggestion();
+        self.display_results();
+        self.status(&format!("imported {} guesses from {}", rows.len(),
+                             path.display()));
+    }
+
+    fn record_guess(&self, guess: &str, feedback: &Feedback)
+        -> Result<(), Error>
+    {
+        self.dictionary.apply_feedback(guess, feedback)?;
+
+        let states = feedback.states();
+        for (i, (ch, state)) in guess.chars().zip(states).enumerate() {
+            if *state == LetterState::Correct && !self.is_locked(i) {
+                self.positions[i].set_text(&ch.to_string());
+                self.set_locked(i, true);
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_combined(&self) {
+        for mark in self.marks.borrow().iter() {
+            match *mark {
+                Mark::Excluded(ch) => {
+                    self.dictionary.remove_char(SetType::Excluded, ch);
+                },
+
+                Mark::Misplaced(pos, ch) => {
+                    self.dictionary.remove_char(SetType::Included, ch);
+                    self.dictionary.allow_at_position(pos, ch);
+                },
+
+                Mark::Position(pos, _) => {
+                    self.dictionary.unset_char_position(pos);
+                },
+
+                Mark::NotAt(pos, ch) => {
+                    self.dictionary.allow_at_position(pos, ch);
+                }
+            }
+        }
+
+        let marks = self.combined_marks();
+        for mark in &marks {
+            match *mark {
+                Mark::Excluded(ch) => {
+                    self.dictionary.add_char(SetType::Excluded, ch);
+                },
+
+                Mark::Misplaced(pos, ch) => {
+                    self.dictionary.add_char(SetType::Included, ch);
+                    self.dictionary.exclude_at_position(pos, ch);
+                },
+
+                Mark::Position(pos, ch) => {
+                    self.dictionary.set_char_position(pos, ch);
+                },
+
+                Mark::NotAt(pos, ch) => {
+                    self.dictionary.exclude_at_position(pos, ch);
+                }
+            }
+        }
+        *self.marks.borrow_mut() = marks;
+
+        self.refresh();
+        self.display_results();
+        self.status("updated the combined constraints");
+    }
+
+    fn combined_marks(&self) -> Vec<Mark> {
+        let buffer = self.combined.buffer()
+            .expect("Couldn't get constraints buffer.");
+        let tags = buffer.tag_table()
+            .expect("Couldn't get constraints tag table.");
+
+        let mut letters = Vec::new();
+        let mut iter = buffer.start_iter();
+        let mut pos = 0;
+        while !iter.is_end() {
+            let ch = iter.char().unwrap_or_default();
+            if ch == '\n' {
+                pos = 0;
+            } else if ch.is_alphabetic() {
+                if pos < self.length() {
+                    let ch = ch.to_lowercase().next().unwrap_or(ch);
+                    letters.push((pos + 1, ch, tag_state(&tags, &iter)));
+                }
+                pos += 1;
+            }
+            iter.forward_char();
+        }
+
+        let known = |ch: char| letters.iter()
+            .any(|(_, c, state)| *c == ch && *state != LetterState::Absent);
+        letters.iter()
+            .map(|(pos, ch, state)| match state {
+                LetterState::Correct => Mark::Position(*pos, *ch),
+                LetterState::Present => Mark::Misplaced(*pos, *ch),
+                LetterState::Absent if known(*ch) => Mark::NotAt(*pos, *ch),
+                LetterState::Absent => Mark::Excluded(*ch)
+            })
+            .collect()
+    }
+
+    fn refresh(&self) {
+        let chars = self.dictionary.excluded_chars();
+        let mut excluded = String::new();
+        for ch in chars.iter() { excluded.push(*ch); }
+
+        let chars = self.dictionary.included_chars();
+        let mut included = String::new();
+        for ch in chars.iter() {
+            let count = match self.counts_repeats(SetType::Included) {
+                true => self.dictionary.min_count(*ch).max(1),
+                false => 1
+            };
+            for _ in 0..count { included.push(*ch); }
+        }
+
+        // When accumulating, the entry only collects new letters and the
+        // full set is shown as its placeholder.
+        let accumulate = self.accumulates(SetType::Excluded);
+        let signal_id = self.signal_ids.signal(SetType::Excluded);
+        self.exclude.block_signal(&signal_id);
+        match accumulate {
+            true => self.exclude.set_text(""),
+            false => self.exclude.set_text(&excluded)
+        }
+        self.exclude.unblock_signal(&signal_id);
+        self.exclude.set_placeholder_text(match accumulate {
+            true => Some(&excluded),
+            false => None
+        });
+
+        let signal_id = self.signal_ids.signal(SetType::Included);
+        self.include.block_signal(&signal_id);
+        self.include.set_text(&included);
+        self.include.unblock_signal(&signal_id);
+
+        self.refresh_keyboard();
+    }
+
+    fn is_locked(&self, index: usize) -> bool {
+        self.locked.borrow()[index]
+    }
+
+    fn set_locked(&self, index: usize, locked: bool) {
+        self.locked.borrow_mut()[index] = locked;
+
+        let entry = &self.positions[index];
+        entry.set_editable(!locked);
+        if locked {
+            entry.style_context().add_class("locked");
+        } else {
+            entry.style_context().remove_class("locked");
+        }
+    }
+
+    fn update_position(&self, index: usize, old: &str, new: &str) {
+        let pos = index + 1;
+        match parse_position(old) {
+            (true, letters) => {
+                for ch in letters {
+                    self.dictionary.allow_at_position(pos, ch);
+                }
+            },
+            (false, letters) => match letters.len() {
+                0 => (),
+                1 => self.dictionary.unset_char_position(pos),
+                _ => self.dictionary.clear_allowed_at(pos)
+            }
+        }
+
+        let style = self.positions[index].style_context();
+        style.remove_class("excluded");
+        let (negated, letters) = parse_position(new);
+        let listed = letters.iter().collect::<String>();
+        let message = match (negated, letters.as_slice()) {
+            (true, []) => {
+                format!("type a letter to exclude from position {}", pos)
+            },
+
+            (true, letters) => {
+                for ch in letters {
+                    self.dictionary.exclude_at_position(pos, *ch);
+                }
+                style.add_class("excluded");
+                format!("excluded '{}' from position {}", listed, pos)
+            },
+
+            (false, []) => format!("cleared position {}", pos),
+
+            (false, [ch]) => {
+                self.dictionary.set_char_position(pos, *ch);
+                format!("set position {} to '{}'", pos, ch)
+            },
+
+            (false, letters) => {
+                self.dictionary.set_allowed_at(pos, letters);
+                format!("position {} is one of '{}'", pos, listed)
+            }
+        };
+
+        self.refresh_keyboard();
+        self.display_results();
+        self.status(&message);
+    }
+
+    fn accumulates(&self, set_type: SetType) -> bool {
+        matches!(set_type, SetType::Excluded) && *self.accumulate.borrow()
+    }
+
+    fn counts_repeats(&self, set_type: SetType) -> bool {
+        matches!(set_type, SetType::Included) && *self.count_repeats.borrow()
+    }
+
+    fn sync_include_counts(&self, text: &str) {
+        self.dictionary.clear_set(SetType::Included);
+        self.dictionary.clear_min_counts();
+
+        let mut counts = HashMap::new();
+        for ch in text.to_lowercase().chars() {
+            if self.dictionary.is_letter(ch) {
+                self.dictionary.add_char(SetType::Included, ch);
+                *counts.entry(ch).or_insert(0) += 1;
+            }
+        }
+
+        for (ch, count) in counts {
+            if count > 1 {
+                self.dictionary.set_min_count(ch, count);
+            }
+        }
+    }
+
+    fn refresh_position_hints(&self) {
+        for (i, entry) in self.positions.iter().enumerate() {
+            let hint = self.dictionary.position_letter_counts(i+1).iter()
+                .map(|(ch, count)| format!("{} {}", ch, count))
+                .collect::<Vec<_>>()
+                .join("\n");
+            entry.set_tooltip_text(Some(&hint));
+        }
+    }
+
+    fn refresh_useful_letters(&self) {
+        let letters = self.dictionary.letters_by_usefulness().iter()
+            .take(USEFUL_LETTERS)
+            .map(|(ch, score)| format!("{} {:.2}", ch.to_uppercase(), score))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.useful.set_text(&letters);
+    }
+
+    fn refresh_word_check(&self) {
+        let word = self.check.text().to_lowercase();
+        let icon = gtk::EntryIconPosition::Secondary;
+        if word.is_empty() {
+            self.check.set_icon_from_icon_name(icon, None);
+            return;
+        }
+
+        let known = self.dictionary.contains_word(&word);
+        let matches = self.dictionary.is_match(&word);
+        let (name, tooltip) = match (known, matches) {
+            (true, true) => ("object-select-symbolic",
+                             "in the dictionary and matches"),
+            (true, false) => ("window-close-symbolic",
+                              "in the dictionary but ruled out"),
+            (false, true) => ("dialog-warning-symbolic",
+                              "matches but is not in the dictionary"),
+            (false, false) => ("window-close-symbolic",
+                               "not in the dictionary and ruled out")
+        };
+        self.check.set_icon_from_icon_name(icon, Some(name));
+        self.check.set_icon_tooltip_text(icon, Some(tooltip));
+    }
+
+    fn refresh_stats(&self, count: usize) {
+        let mut stats = format!("started {}, now {}",
+                                self.dictionary.initial_count(), count);
+        if let Some(bits) = self.dictionary.information_gained() {
+            stats.push_str(&format!(" (log2 reduction {:.1} bits)", bits));
+        }
+        self.stats.set_text(&stats);
+    }
+
+    fn refresh_keyboard(&self) {
+        let tried = self.dictionary.tried_chars();
+        for (ch, button) in &self.keyboard {
+            let context = button.style_context();
+            if tried.contains(ch) {
+                context.add_class("dim-label");
+            } else {
+                context.remove_class("dim-label");
+            }
+        }
+
+        let untried = self.dictionary.untried_letters();
+        let letters = untried.iter()
+            .map(|ch| ch.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.untried.set_text(&format!("{} letters untried: {}",
+                                       untried.len(), letters));
+    }
+
+    // Letters ruled out by the candidates rather than by the user are
+    // struck through instead of dimmed.
+    fn refresh_impossible_letters(&self) {
+        let impossible = match *self.show_impossible.borrow() {
+            true => self.dictionary.impossible_letters(),
+            false => HashSet::new()
+        };
+
+        for (ch, button) in &self.keyboard {
+            let label = match button.child()
+                .and_then(|child| child.downcast::<gtk::Label>().ok())
+            {
+                Some(label) => label,
+                None => continue
+            };
+
+            let text = ch.to_uppercase().to_string();
+            if impossible.contains(ch) {
+                label.set_markup(&format!("<s>{}</s>", text));
+                button.set_tooltip_text(Some("No remaining candidate uses \
+                                              this letter"));
+            } else {
+                label.set_text(&text);
+                button.set_tooltip_text(None);
+            }
+        }
+    }
+
+    fn display_results(&self) {
+        *self.page.borrow_mut() = 0;
+        if *self.inverted.borrow() || *self.answers_only.borrow() ||
+            !self.search.text().is_empty()
+        {
+            self.display_page();
+        } else {
+            self.stream_results();
+        }
+    }
+
+    fn stream_results(&self) {
+        self.cancel_stream();
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        buffer.set_text("");
+        self.previous.set_sensitive(false);
+        self.next.set_sensitive(false);
+        self.page_label.set_text("Filtering...");
+
+        let mut stream = self.dictionary.matches_stream(STREAM_BATCH);
+        let mut shown: Vec<String> = Vec::new();
+        let tab_ptr = self.this.clone();
+        let source = glib::idle_add_local(move || {
+            let rc = match tab_ptr.upgrade() {
+                Some(rc) => rc,
+                None => return Continue(false)
+            };
+            let tab: &Tab = rc.borrow();
+
+            match stream.next_batch(&tab.dictionary) {
+                Some(words) => {
+                    let take = words.len().min(RESULTS_PER_PAGE - shown.len());
+                    if take > 0 {
+                        shown.extend_from_slice(&words[..take]);
+                        let mut results = String::new();
+                        push_words(&mut results, &shown, tab.result_columns());
+                        buffer.set_text(&results);
+                    }
+                    Continue(true)
+                },
+
+                None => {
+                    tab.stream.borrow_mut().take();
+                    tab.display_page();
+                    Continue(false)
+                }
+            }
+        });
+        *self.stream.borrow_mut() = Some(source);
+    }
+
+    fn cancel_stream(&self) {
+        if let Some(source) = self.stream.borrow_mut().take() {
+            source.remove();
+        }
+    }
+
+    fn display_page(&self) {
+        self.cancel_stream();
+
+        let page = *self.page.borrow();
+        let columns = self.result_columns();
+        let query = self.search.text().to_lowercase();
+        let mut results = String::new();
+
+        let pages = if *self.inverted.borrow() {
+            let words = search_words(self.dictionary.non_matches(), &query);
+            push_page(&mut results, &words, page, columns)
+        } else if *self.answers_only.borrow() {
+            let words = search_words(self.dictionary.matches_answers_only(),
+                                     &query);
+            push_page(&mut results, &words, page, columns)
+        } else if !query.is_empty() {
+            let words = self.dictionary.remaining().to_vec();
+            push_page(&mut results, &search_words(words, &query), page,
+                      columns)
+        } else {
+            let (matches, pages) = self.dictionary
+                .matches_paged(page, RESULTS_PER_PAGE);
+            push_words(&mut results, &matches, columns);
+            pages
+        };
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        buffer.set_text(&results);
+
+        self.previous.set_sensitive(page > 0);
+        self.next.set_sensitive(page + 1 < pages);
+        self.page_label.set_text(&format!("Page {} of {}",
+                                          page + 1, pages.max(1)));
+
+        let pending = self.pending_status.borrow_mut().take();
+        if let Some(action) = pending {
+            self.status(&action);
+        }
+    }
+
+    fn change_page(&self, forward: bool) {
+        let mut page = self.page.borrow_mut();
+        if forward {
+            *page += 1;
+        } else if *page > 0 {
+            *page -= 1;
+        }
+        drop(page);
+        self.display_page();
+    }
+
+    fn refresh_history(&self) {
+        for row in self.history.children() {
+            self.history.remove(&row);
+        }
+
+        for (guess, feedback) in &*self.dictionary.history() {
+            let text = format!("{} {}", guess.to_uppercase(),
+                               feedback.to_emoji());
+            self.history.add(&gtk::Label::new(Some(&text)));
+        }
+        self.history.show_all();
+    }
+
+    fn clear(&self) {
+        self.refresh();
+        self.refresh_history();
+        self.clear_suggestion();
+
+        self.marks.borrow_mut().clear();
+        let buffer = self.combined.buffer()
+            .expect("Couldn't get constraints buffer.");
+        buffer.set_text("");
+
+        for (i, entry) in self.positions.iter().enumerate() {
+            self.set_locked(i, false);
+            entry.set_text("");
+        }
+
+        let buffer = self.results.buffer()
+            .expect("Couldn't get results buffer.");
+        buffer.set_text("");
+
+        *self.page.borrow_mut() = 0;
+        self.page_label.set_text("");
+        self.previous.set_sensitive(false);
+        self.next.set_sensitive(false);
+    }
+
+    fn set_signal_ids(&self, include: SignalHandlerId,
+                             exclude: SignalHandlerId)
+    {
+        *self.signal_ids.include.borrow_mut() = Some(include);
+        *self.signal_ids.exclude.borrow_mut() = Some(exclude);
+    }
+
+    fn set_insert_signal_ids(&self, include: SignalHandlerId,
+                                    exclude: SignalHandlerId)
+    {
+        *self.insert_signal_ids.include.borrow_mut() = Some(include);
+        *self.insert_signal_ids.exclude.borrow_mut() = Some(exclude);
+    }
+}
+
+fn new_tab(dictionary: Dictionary, statusbar: &gtk::Statusbar) -> Rc<Tab> {
+    let position_css = gtk::CssProvider::new();
+    position_css.load_from_data(POSITION_CSS.as_bytes())
+        .expect("Couldn't load position style.");
+
+    let mut positions = Vec::new();
+    for i in 0usize..dictionary.length() {
+        let entry = gtk::Entry::new();
+        entry.set_max_length(POSITION_LETTERS);
+        entry.style_context().add_provider(
+            &position_css, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION);
+        unsafe { entry.set_data("index", i); }
+        positions.push(entry);
+    }
+
+    let results = gtk::TextView::new();
+    results.set_cursor_visible(false);
+    results.set_editable(false);
+
+    let highlight = gtk::TextTag::builder()
+        .name("highlight")
+        .background("#fce94f")
+        .build();
+    results.buffer()
+        .and_then(|buffer| buffer.tag_table())
+        .expect("Couldn't get results tag table.")
+        .add(&highlight);
+
+    let results_css = gtk::CssProvider::new();
+    results.style_context()
+        .add_provider(&results_css, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION);
+
+    let combined = gtk::TextView::new();
+    combined.set_monospace(true);
+    combined.set_left_margin(4);
+    combined.set_pixels_above_lines(2);
+    let tags = combined.buffer()
+        .and_then(|buffer| buffer.tag_table())
+        .expect("Couldn't get constraints tag table.");
+    for (_, name, color) in COMBINED_TAGS {
+        let tag = gtk::TextTag::builder()
+            .name(name)
+            .background(color)
+            .foreground("white")
+            .build();
+        tags.add(&tag);
+    }
+
+    let search = gtk::SearchEntry::new();
+    search.set_placeholder_text(Some("Filter results"));
+
+    let guess = gtk::Entry::new();
+    guess.set_max_length(dictionary.length() as i32);
+
+    let check = gtk::Entry::new();
+    check.set_max_length(dictionary.length() as i32);
+
+    let feedback = gtk::Entry::new();
+    feedback.set_max_length(dictionary.length() as i32);
+    feedback.set_placeholder_text(Some("G = green, Y = yellow, X = gray"));
+
+    let constraints = CONSTRAINTS.iter()
+        .map(|(constraint, label)| {
+            let button = gtk::CheckButton::with_label(label);
+            button.set_active(dictionary.is_enabled(*constraint));
+            (*constraint, button)
+        })
+        .collect();
+
+    let mut keyboard = Vec::new();
+    let extra = dictionary.alphabet().into_iter()
+        .filter(|ch| !KEYBOARD_ROWS.iter().any(|row| row.contains(*ch)));
+    for ch in KEYBOARD_ROWS.iter().flat_map(|row| row.chars()).chain(extra) {
+        let button = gtk::Button::with_label(&ch.to_uppercase().to_string());
+        button.set_can_focus(false);
+        keyboard.push((ch, button));
+    }
+
+    let previous = gtk::Button::with_mnemonic("_Previous");
+    previous.set_sensitive(false);
+    let next = gtk::Button::with_mnemonic("_Next");
+    next.set_sensitive(false);
+
+    Rc::new_cyclic(|this| Tab {
+        this: this.clone(),
+        dictionary: dictionary,
+        include: gtk::Entry::new(),
+        exclude: gtk::Entry::new(),
+        separate: gtk::Box::new(gtk::Orientation::Vertical, 8),
+        combined,
+        combined_box: gtk::Box::new(gtk::Orientation::Vertical, 4),
+        marks: RefCell::new(Vec::new()),
+        results: results,
+        results_label: gtk::Label::new(Some("Results")),
+        search: search,
+        results_css: results_css,
+        stats: gtk::Label::new(None),
+        page: RefCell::new(0),
+        columns: RefCell::new(1),
+        stream: RefCell::new(None),
+        pending_status: RefCell::new(None),
+        inverted: RefCell::new(false),
+        answers_only: RefCell::new(false),
+        count_repeats: RefCell::new(false),
+        accumulate: RefCell::new(false),
+        show_impossible: RefCell::new(false),
+        page_label: gtk::Label::new(None),
+        previous: previous,
+        next: next,
+        history: gtk::ListBox::new(),
+        useful: gtk::Label::new(None),
+        statusbar: statusbar.clone(),
+        signal_ids: SignalIds::new(),
+        insert_signal_ids: SignalIds::new(),
+        locked: RefCell::new(vec![false; positions.len()]),
+        position_signals: RefCell::new(Vec::new()),
+        position_insert_signals: RefCell::new(Vec::new()),
+        suggested: RefCell::new(Vec::new()),
+        clear_positions: gtk::Button::with_mnemonic("C_lear"),
+        positions: positions,
+        guess: guess,
+        feedback: feedback,
+        apply: gtk::Button::with_mnemonic("_Apply"),
+        check,
+        check_box: gtk::Box::new(gtk::Orientation::Horizontal, 8),
+        constraints: constraints,
+        keyboard: keyboard,
+        untried: gtk::Label::new(None)
+    })
+}
+
+fn build_character_entry(label: &str, entry: &gtk::Entry, description: &str)
+    -> gtk::Box
+{
+    let hbox = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+    let vbox = gtk::Box::new(gtk::Orientation::Vertical, 8);
+    let label = gtk::Label::with_mnemonic(label);
+    label.set_mnemonic_widget(Some(entry));
+    set_accessible(entry, &label.text(), description);
+    vbox.add(&hbox);
+    hbox.add(&label);
+    vbox.add(entry);
+    vbox.set_focus_chain(&[entry.clone().upcast()]);
+    vbox
+}
+
+fn set_accessible<W: IsA<gtk::Widget>>(widget: &W, name: &str,
+                                      description: &str)
+{
+    if let Some(accessible) = widget.accessible() {
+        accessible.set_name(name);
+        accessible.set_description(description);
+    }
+}
+
+fn push_words(results: &mut String, words: &[String], columns: usize) {
+    let width = words.iter()
+        .map(|word| display_width(word))
+        .max()
+        .unwrap_or(0);
+
+    for row in words.chunks(columns.max(1)) {
+        let row = row.iter()
+            .map(|word| {
+                let padding = width - display_width(word);
+                format!("{}{}", word, " ".repeat(padding))
+            })
+            .collect::<Vec<_>>();
+        results.push_str(&row.join(COLUMN_GAP));
+        results.push('\n');
+    }
+}
+
+fn push_page(results: &mut String, words: &[String], page: usize,
+             columns: usize) -> usize
+{
+    let start = (page * RESULTS_PER_PAGE).min(words.len());
+    let end = (start + RESULTS_PER_PAGE).min(words.len());
+    push_words(results, &words[start..end], columns);
+    words.len().div_ceil(RESULTS_PER_PAGE)
+}
+
+fn search_words(words: Vec<String>, query: &str) -> Vec<String> {
+    if query.is_empty() {
+        return words;
+    }
+    words.into_iter().filter(|word| word.contains(query)).collect()
+}
+
+fn set_name(set_type: SetType) -> &'static str {
+    match set_type {
+        SetType::Excluded => "excluded",
+        SetType::Included => "included"
+    }
+}
+
+// A position holds one letter for a green, several letters the position
+// may be one of, or '!' and the letters it can't be.
+fn parse_position(text: &str) -> (bool, Vec<char>) {
+    let (negated, rest) = match text.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, text)
+    };
+
+    let mut letters = Vec::new();
+    for ch in rest.chars().map(|ch| ch.to_ascii_lowercase()) {
+        if !letters.contains(&ch) {
+            letters.push(ch);
+        }
+    }
+    (negated, letters)
+}
+
+fn is_non_include_character(dictionary: &Dictionary, ch: char) -> bool {
+    !(dictionary.is_letter(ch) || ch == ',' || ch == ' ')
+}
+
+fn tag_state(tags: &gtk::TextTagTable, iter: &gtk::TextIter) -> LetterState {
+    COMBINED_TAGS.iter()
+        .find(|(_, name, _)| {
+            tags.lookup(name).is_some_and(|tag| iter.has_tag(&tag))
+        })
+        .map(|(state, _, _)| *state)
+        .unwrap_or(LetterState::Absent)
+}
+
+fn word_at_location(results: &gtk::TextView, x: f64, y: f64)
+    -> Option<(gtk::TextIter, gtk::TextIter)>
+{
+    let (x, y) = results.window_to_buffer_coords(gtk::TextWindowType::Text,
+                                                 x as i32, y as i32);
+    let iter = results.iter_at_location(x, y)?;
+    let is_word = |iter: &gtk::TextIter| {
+        iter.char().is_some_and(|ch| !ch.is_whitespace())
+    };
+
+    let location = results.iter_location(&iter);
+    if !is_word(&iter) || x > location.x() + location.width() ||
+        y > location.y() + location.height()
+    {
+        return None;
+    }
+
+    let mut start = iter;
+    loop {
+        let mut previous = start;
+        if !previous.backward_char() || !is_word(&previous) {
+            break;
+        }
+        start = previous;
+    }
+
+    let mut end = iter;
+    while is_word(&end) {
+        end.forward_char();
+    }
+    Some((start, end))
+}
+
+fn connect_delete_text(hook_type: SetType, tab: &Rc<Tab>) -> SignalHandlerId {
+    let entry = match hook_type {
+        SetType::Excluded => &tab.exclude,
+        SetType::Included => &tab.include
+    };
+
+    let tab_ptr = Rc::downgrade(tab);
+    let id = entry.connect_delete_text(move |entry, start, end| {
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+
+        let gstring = entry.text();
+        let s = gstring.as_str();
+        let start: usize = start.try_into().unwrap();
+        let end: usize = end.try_into().unwrap();
+
+        let removed = &s[start..end];
+        if tab.accumulates(hook_type) {
+            return;
+        }
+
+        if tab.counts_repeats(hook_type) {
+            tab.sync_include_counts(&format!("{}{}", &s[..start], &s[end..]));
+        } else {
+            for ch in removed.chars() {
+                tab.dictionary.remove_char(hook_type, ch);
+            }
+        }
+        tab.display_results();
+        tab.status(&format!("removed '{}' from {}", removed,
+                            set_name(hook_type)));
+    });
+
+    return id;
+}
+
+fn connect_focus_out_event(hook_type: SetType, tab: &Rc<Tab>) {
+    let entry = match hook_type {
+        SetType::Excluded => &tab.exclude,
+        SetType::Included => &tab.include
+    };
+
+    let tab_ptr = Rc::downgrade(tab);
+    entry.connect_focus_out_event(move |entry, _| {
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+
+        let gstring = entry.text();
+        let text = gstring.as_str();
+        if tab.counts_repeats(hook_type) {
+            tab.sync_include_counts(text);
+        } else {
+            if !tab.accumulates(hook_type) {
+                tab.dictionary.clear_set(hook_type);
+            }
+            for ch in text.chars() {
+                if tab.dictionary.is_letter(ch) {
+                    tab.dictionary.add_char(hook_type, ch);
+                }
+            }
+        }
+
+        tab.refresh();
+        Inhibit(false)
+    });
+}
+
+fn connect_insert_text(hook_type: SetType, tab: &Rc<Tab>)
+    -> SignalHandlerId
+{
+    let entry = match hook_type {
+        SetType::Excluded => &tab.exclude,
+        SetType::Included => &tab.include
+    };
+
+    let tab_ptr = Rc::downgrade(&tab);
+    entry.connect_insert_text(move |entry, s, _| {
+        if let Some(ch) = s.chars().next() {
+            let rc = tab_ptr.upgrade().unwrap();
+            let tab: &Tab = rc.borrow();
+
+            if is_non_include_character(&tab.dictionary, ch) {
+                gdk::beep();
+                signal::signal_stop_emission_by_name(entry, "insert-text");
+                tab.status(&format!("'{}' is not a valid character", ch));
+                return;
+            }
+
+            if tab.counts_repeats(hook_type) {
+                tab.sync_include_counts(&format!("{}{}", entry.text(), s));
+                tab.refresh_keyboard();
+                tab.display_results();
+                tab.status(&format!("added '{}' to {}", s,
+                                    set_name(hook_type)));
+            } else if tab.dictionary.is_letter(ch) {
+                tab.dictionary.add_char(hook_type, ch);
+                tab.refresh_keyboard();
+                tab.display_results();
+                tab.status(&format!("added '{}' to {}", ch,
+                                    set_name(hook_type)));
+            }
+        }
+    })
+}
+
+fn position_connect_delete_text(tab: &Rc<Tab>) {
+    let mut signals = tab.position_signals.borrow_mut();
+    for (pos, entry) in tab.positions.iter().enumerate() {
+        let tab_ptr = Rc::downgrade(tab);
+        let signal_id = entry.connect_delete_text(move |entry, start, end| {
+            let rc = tab_ptr.upgrade().unwrap();
+            let tab: &Tab = rc.borrow();
+
+            if tab.is_locked(pos) {
+                signal::signal_stop_emission_by_name(entry, "delete-text");
+                return;
+            }
+
+            let text = entry.text();
+            let end = if end < 0 { text.chars().count() as i32 } else { end };
+            let remaining = text.chars().enumerate()
+                .filter(|(i, _)| (*i as i32) < start || (*i as i32) >= end)
+                .map(|(_, ch)| ch)
+                .collect::<String>();
+            tab.update_position(pos, &text, &remaining);
+        });
+        signals.push(signal_id);
+    }
+}
+
+fn clear_positions_connect_clicked(tab: &Rc<Tab>) {
+    let tab_ptr = Rc::downgrade(tab);
+    tab.clear_positions.connect_clicked(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+        tab.clear_positions();
+    });
+}
+
+fn position_connect_insert_text(tab: &Rc<Tab>) {
+    let mut signals = tab.position_insert_signals.borrow_mut();
+    for (pos, entry) in tab.positions.iter().enumerate() {
+        let tab_ptr = Rc::downgrade(tab);
+        let signal_id = entry.connect_insert_text(move |entry, s, at| {
+            let rc = tab_ptr.upgrade().unwrap();
+            let tab: &Tab = rc.borrow();
+
+            if tab.is_locked(pos) {
+                gdk::beep();
+                signal::signal_stop_emission_by_name(entry, "insert-text");
+                tab.status(&format!("position {} is locked", pos+1));
+                return;
+            }
+
+            let text = entry.text();
+            let at = *at as usize;
+            let value = text.chars().take(at)
+                .chain(s.chars())
+                .chain(text.chars().skip(at))
+                .collect::<String>();
+
+            let (_, letters) = parse_position(&value);
+            match letters.iter().find(|ch| !tab.dictionary.is_letter(**ch)) {
+                Some(ch) => {
+                    gdk::beep();
+                    signal::signal_stop_emission_by_name(entry, "insert-text");
+                    tab.status(&format!("'{}' is not a valid character; enter \
+                                         letters, or '!' and letters to \
+                                         exclude", ch));
+                },
+
+                None => tab.update_position(pos, &text, &value)
+            }
+        });
+        signals.push(signal_id);
+    }
+}
+
+fn position_connect_button_press_event(tab: &Rc<Tab>) {
+    for (pos, entry) in tab.positions.iter().enumerate() {
+        let tab_ptr = Rc::downgrade(tab);
+        entry.connect_button_press_event(move |entry, event| {
+            if event.button() != 3 || entry.text().is_empty() {
+                return Inhibit(false);
+            }
+
+            let rc = tab_ptr.upgrade().unwrap();
+            let tab: &Tab = rc.borrow();
+
+            let locked = !tab.is_locked(pos);
+            tab.set_locked(pos, locked);
+            tab.status(&format!("{} position {}",
+                                if locked { "locked" } else { "unlocked" },
+                                pos+1));
+            Inhibit(true)
+        });
+    }
+}
+
+fn position_connect_focus_out_event(tab: &Rc<Tab>) {
+    for entry in &tab.positions {
+        let tab_ptr = Rc::downgrade(tab);
+
+        entry.connect_focus_out_event(move |_, _| {
+            let rc = tab_ptr.upgrade().unwrap();
+            let tab: &Tab = rc.borrow();
+
+            tab.refresh();
+            Inhibit(false)
+        });
+    }
+}
+
+fn guess_connect_activate(tab: &Rc<Tab>) {
+    let tab_ptr = Rc::downgrade(tab);
+    tab.apply.connect_clicked(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        rc.apply_guess();
+    });
+
+    for entry in [&tab.guess, &tab.feedback] {
+        let tab_ptr = Rc::downgrade(tab);
+        entry.connect_activate(move |_| {
+            let rc = tab_ptr.upgrade().unwrap();
+            rc.apply_guess();
+        });
+    }
+}
+
+fn check_connect_changed(tab: &Rc<Tab>) {
+    let tab_ptr = Rc::downgrade(tab);
+    tab.check.connect_changed(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        rc.refresh_word_check();
+    });
+}
+
+fn page_connect_clicked(tab: &Rc<Tab>) {
+    let tab_ptr = Rc::downgrade(tab);
+    tab.previous.connect_clicked(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        rc.change_page(false);
+    });
+
+    let tab_ptr = Rc::downgrade(tab);
+    tab.next.connect_clicked(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        rc.change_page(true);
+    });
+}
+
+fn search_connect_search_changed(tab: &Rc<Tab>) {
+    let tab_ptr = Rc::downgrade(tab);
+    tab.search.connect_search_changed(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+        tab.display_results();
+    });
+}
+
+fn constraint_connect_toggled(tab: &Rc<Tab>) {
+    for ((constraint, button), (_, label)) in
+        tab.constraints.iter().zip(CONSTRAINTS)
+    {
+        let tab_ptr = Rc::downgrade(tab);
+        let constraint = *constraint;
+        let label = label.to_lowercase();
+        button.connect_toggled(move |button| {
+            let rc = tab_ptr.upgrade().unwrap();
+            let tab: &Tab = rc.borrow();
+
+            let enabled = button.is_active();
+            tab.dictionary.set_enabled(constraint, enabled);
+            tab.display_results();
+            tab.status(&format!("{} {} constraints",
+                                if enabled { "enabled" } else { "paused" },
+                                label));
+        });
+    }
+}
+
+fn keyboard_connect_clicked(tab: &Rc<Tab>) {
+    for (ch, button) in &tab.keyboard {
+        let tab_ptr = Rc::downgrade(tab);
+        let ch = *ch;
+        button.connect_clicked(move |_| {
+            let rc = tab_ptr.upgrade().unwrap();
+            let tab: &Tab = rc.borrow();
+
+            let mut pos = tab.guess.text_length() as i32;
+            tab.guess.insert_text(&ch.to_string(), &mut pos);
+        });
+    }
+}
+
+fn results_connect_button_press_event(tab: &Rc<Tab>) {
+    let tab_ptr = Rc::downgrade(tab);
+    tab.results.connect_button_press_event(move |results, event| {
+        let (x, y) = event.position();
+        let (start, end) = match word_at_location(results, x, y) {
+            Some(bounds) => bounds,
+            None => return Inhibit(false)
+        };
+
+        let buffer = results.buffer()
+            .expect("Couldn't get results buffer.");
+        let word = match buffer.text(&start, &end, false) {
+            Some(word) => String::from(word.trim_end()),
+            None => return Inhibit(false)
+        };
+
+        let rc = tab_ptr.upgrade().unwrap();
+        match event.button() {
+            1 => {
+                rc.copy_word(&word);
+
+                buffer.apply_tag_by_name("highlight", &start, &end);
+                glib::timeout_add_local(Duration::from_millis(500), move || {
+                    let (start, end) = buffer.bounds();
+                    buffer.remove_tag_by_name("highlight", &start, &end);
+                    Continue(false)
+                });
+                Inhibit(true)
+            },
+
+            3 => {
+                popup_results_menu(&rc, &word, event);
+                Inhibit(true)
+            },
+
+            _ => Inhibit(false)
+        }
+    });
+}
+
+fn popup_results_menu(tab: &Rc<Tab>, word: &str, event: &gdk::EventButton) {
+    let menu = gtk::Menu::new();
+    let copy = gtk::MenuItem::with_mnemonic("_Copy");
+    let exclude = gtk::MenuItem::with_mnemonic("E_xclude Its Letters");
+    let guess = gtk::MenuItem::with_mnemonic("_Use as Guess");
+    let remove = gtk::MenuItem::with_mnemonic("_Remove From Dictionary");
+
+    let tab_ptr = Rc::downgrade(tab);
+    let word_copy = String::from(word);
+    copy.connect_activate(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+        tab.copy_word(&word_copy);
+    });
+
+    let tab_ptr = Rc::downgrade(tab);
+    let word_copy = String::from(word);
+    exclude.connect_activate(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+        tab.exclude_letters(&word_copy);
+    });
+
+    let tab_ptr = Rc::downgrade(tab);
+    let word_copy = String::from(word);
+    guess.connect_activate(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+        tab.use_as_guess(&word_copy);
+    });
+
+    let tab_ptr = Rc::downgrade(tab);
+    let word = String::from(word);
+    remove.connect_activate(move |_| {
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+
+        match tab.dictionary.blacklist_word(&word) {
+            Ok(()) => {
+                tab.display_results();
+                tab.status(&format!("removed '{}' from the dictionary",
+                                    word));
+            },
+
+            Err(error) => {
+                tab.status(&format!("unable to remove '{}': {}",
+                                    word, error));
+            }
+        }
+    });
+
+    menu.append(&copy);
+    menu.append(&exclude);
+    menu.append(&guess);
+    menu.append(&gtk::SeparatorMenuItem::new());
+    menu.append(&remove);
+    menu.show_all();
+    menu.popup_easy(event.button(), event.time());
+}
+
+fn combined_connect_changed(tab: &Rc<Tab>) {
+    let tab_ptr = Rc::downgrade(tab);
+    let buffer = tab.combined.buffer()
+        .expect("Couldn't get constraints buffer.");
+    buffer.connect_changed(move |buffer| {
+        let tags = buffer.tag_table()
+            .expect("Couldn't get constraints tag table.");
+        let names = COMBINED_TAGS.iter()
+            .map(|(_, name, _)| *name).collect::<Vec<_>>();
+
+        let mut iter = buffer.start_iter();
+        while !iter.is_end() {
+            let mut next = iter;
+            next.forward_char();
+            let tagged = names.iter()
+                .filter_map(|name| tags.lookup(name))
+                .any(|tag| iter.has_tag(&tag));
+            if iter.char().is_some_and(char::is_alphabetic) && !tagged {
+                buffer.apply_tag_by_name("absent", &iter, &next);
+            }
+            iter = next;
+        }
+
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+        tab.apply_combined();
+    });
+}
+
+fn combined_connect_button_press_event(tab: &Rc<Tab>) {
+    let tab_ptr = Rc::downgrade(tab);
+    tab.combined.connect_button_press_event(move |combined, event| {
+        if event.button() != 1 {
+            return Inhibit(false);
+        }
+
+        let (x, y) = event.position();
+        let (x, y) = combined.window_to_buffer_coords(
+            gtk::TextWindowType::Text, x as i32, y as i32);
+        let start = match combined.iter_at_location(x, y) {
+            Some(iter) if iter.char().is_some_and(char::is_alphabetic) => {
+                iter
+            },
+            _ => return Inhibit(false)
+        };
+        let mut end = start;
+        end.forward_char();
+
+        let buffer = combined.buffer()
+            .expect("Couldn't get constraints buffer.");
+        let tags = buffer.tag_table()
+            .expect("Couldn't get constraints tag table.");
+        let state = tag_state(&tags, &start);
+        let index = COMBINED_TAGS.iter()
+            .position(|(s, _, _)| *s == state)
+            .unwrap_or(0);
+        let (_, next, _) = COMBINED_TAGS[(index + 1) % COMBINED_TAGS.len()];
+
+        for (_, name, _) in COMBINED_TAGS {
+            buffer.remove_tag_by_name(name, &start, &end);
+        }
+        buffer.apply_tag_by_name(next, &start, &end);
+
+        let rc = tab_ptr.upgrade().unwrap();
+        let tab: &Tab = rc.borrow();
+        tab.apply_combined();
+        Inhibit(true)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn push_words_pads_wide_characters() {
+        let mut results = String::new();
+        push_words(&mut results, &words(&["日本", "abc", "xyzw"]), 2);
+        assert_eq!(results, "日本  abc \nxyzw\n");
+    }
+
+    #[test]
+    fn push_words_ignores_combining_marks() {
+        let mut results = String::new();
+        push_words(&mut results, &words(&["cafe\u{301}", "abc"]), 2);
+        assert_eq!(results, "cafe\u{301}  abc \n");
+    }
+}
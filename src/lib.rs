@@ -0,0 +1,6 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+pub mod dictionary;
+pub use dictionary::Dictionary;
@@ -2,10 +2,18 @@
 // Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
 //
 
-mod dictionary;
-use dictionary::Dictionary;
+use std::env;
+use std::path::{Path,PathBuf};
+use std::process;
 
+use girdle::dictionary::{Punctuation,Result,Warning,WordList};
+
+mod cli;
+mod config;
 mod gtk;
+#[cfg(feature = "tui")]
+mod tui;
+use crate::cli::Args;
 use crate::gtk::UI;
 
 const ID: &str = "com.github.rtgill82.girdle";
@@ -15,6 +23,199 @@ const DICTIONARIES: &[&str] = &[
     "/usr/dict/words"
 ];
 
+const DEFAULT_DATA_DIRS: &str = "/usr/local/share:/usr/share";
+const DATA_DICTIONARY: &str = "dict/words";
+
+const WORD_LENGTHS: &[usize] = &[4, 5, 6, 7];
+const DEFAULT_LENGTH: usize = 5;
+
+fn data_dir() -> Option<String> {
+    match env::var("XDG_DATA_HOME") {
+        Ok(dir) if !dir.is_empty() => Some(dir),
+        _ => env::var("HOME").ok()
+            .map(|home| format!("{}/.local/share", home))
+    }
+}
+
+fn data_dirs() -> Vec<String> {
+    let dirs = match env::var("XDG_DATA_DIRS") {
+        Ok(dirs) if !dirs.is_empty() => dirs,
+        _ => String::from(DEFAULT_DATA_DIRS)
+    };
+    dirs.split(':')
+        .filter(|dir| !dir.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn default_dictionaries() -> Vec<PathBuf> {
+    dictionary_paths(data_dir(), &data_dirs())
+}
+
+// The usual system word lists come first, then the user's and system data
+// directories in XDG order.
+fn dictionary_paths(data_home: Option<String>, data_dirs: &[String])
+    -> Vec<PathBuf>
+{
+    let mut dictionaries = DICTIONARIES.iter()
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+
+    let dirs = data_home.iter().chain(data_dirs);
+    for dir in dirs {
+        let path = Path::new(&dir).join(DATA_DICTIONARY);
+        if !dictionaries.contains(&path) {
+            dictionaries.push(path);
+        }
+    }
+    dictionaries
+}
+
+fn blacklist_path() -> Option<String> {
+    data_dir().map(|dir| format!("{}/girdle/blacklist", dir))
+}
+
+fn config_dir() -> Option<String> {
+    match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => Some(dir),
+        _ => env::var("HOME").ok()
+            .map(|home| format!("{}/.config", home))
+    }
+}
+
+fn config_path() -> Option<String> {
+    config_dir().map(|dir| format!("{}/girdle/config", dir))
+}
+
+// Only a missing default word list falls back to the embedded one; a list
+// given on the command line has to exist.
+#[cfg(feature = "embedded-dict")]
+fn use_embedded(dict: Option<&Path>, dictionaries: &[PathBuf]) -> bool {
+    dict.is_none() && !dictionaries.iter().any(|path| path.exists())
+}
+
+fn load_word_list(dict: Option<&Path>) -> Result<WordList> {
+    let dictionaries = match dict {
+        Some(dict) => vec![dict.to_path_buf()],
+        None => default_dictionaries()
+    };
+
+    let blacklist = blacklist_path();
+    #[cfg(feature = "embedded-dict")]
+    if use_embedded(dict, &dictionaries) {
+        return WordList::embedded(Punctuation::Skip,
+                                  blacklist.as_deref().map(Path::new));
+    }
+
+    match blacklist {
+        Some(blacklist) => WordList::with_blacklist(&dictionaries,
+                                                    Punctuation::Skip,
+                                                    &blacklist),
+        None => WordList::load(&dictionaries, Punctuation::Skip)
+    }
+}
+
+fn load_word_list_with_warnings(dict: Option<&Path>)
+    -> Result<(WordList, Vec<Warning>)>
+{
+    let dictionaries = match dict {
+        Some(dict) => vec![dict.to_path_buf()],
+        None => default_dictionaries()
+    };
+
+    let blacklist = blacklist_path();
+    let blacklist = blacklist.as_deref().map(Path::new);
+    #[cfg(feature = "embedded-dict")]
+    if use_embedded(dict, &dictionaries) {
+        return WordList::embedded_with_warnings(Punctuation::Skip, blacklist);
+    }
+
+    WordList::load_with_warnings(&dictionaries, Punctuation::Skip, blacklist)
+}
+
+fn run_headless(args: &Args) -> ! {
+    if let Err(error) = cli::run(args) {
+        eprintln!("girdle: {}", error);
+        process::exit(1);
+    }
+    process::exit(0);
+}
+
 fn main() {
-    UI::run(ID);
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("girdle: {}\n\n{}", error, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    if args.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
+    if args.version {
+        println!("{}", cli::version());
+        return;
+    }
+
+    #[cfg(feature = "tui")]
+    if args.tui {
+        if let Err(error) = tui::run(&args) {
+            eprintln!("girdle: {}", error);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if args.is_headless() {
+        run_headless(&args);
+    }
+
+    if let Err(error) = UI::init() {
+        if args.has_constraints() {
+            run_headless(&args);
+        }
+
+        eprintln!("girdle: {}.", error);
+        eprintln!("girdle: a graphical display is required; pass \
+                   constraint options or --json to run without one.");
+        process::exit(1);
+    }
+
+    UI::run(ID, &args);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dirs(dirs: &[&str]) -> Vec<String> {
+        dirs.iter().map(|dir| dir.to_string()).collect()
+    }
+
+    #[test]
+    fn dictionary_paths_follow_xdg_order() {
+        let home = Some(String::from("/home/user/.local/share"));
+        let data_dirs = dirs(&["/opt/share", "/usr/share"]);
+        let paths = dictionary_paths(home, &data_dirs);
+        assert_eq!(paths, [
+            PathBuf::from("/usr/share/dict/words"),
+            PathBuf::from("/usr/dict/words"),
+            PathBuf::from("/home/user/.local/share/dict/words"),
+            PathBuf::from("/opt/share/dict/words")
+        ]);
+    }
+
+    #[test]
+    fn dictionary_paths_without_a_data_home() {
+        let paths = dictionary_paths(None, &dirs(&["/usr/local/share"]));
+        assert_eq!(paths, [
+            PathBuf::from("/usr/share/dict/words"),
+            PathBuf::from("/usr/dict/words"),
+            PathBuf::from("/usr/local/share/dict/words")
+        ]);
+        assert_eq!(dictionary_paths(None, &[]).len(), DICTIONARIES.len());
+    }
 }
@@ -0,0 +1,365 @@
+//
+// Copyright (c) 2022, Robert Gill <rtgill82@gmail.com>
+//
+
+use std::io;
+use std::io::Write;
+
+use crossterm::{execute,queue};
+use crossterm::cursor::{Hide,MoveTo,Show};
+use crossterm::event;
+use crossterm::event::{Event,KeyCode,KeyEvent,KeyEventKind,KeyModifiers};
+use crossterm::style::{Attribute,Print,SetAttribute};
+use crossterm::terminal;
+use crossterm::terminal::{Clear,ClearType};
+use crossterm::terminal::{EnterAlternateScreen,LeaveAlternateScreen};
+
+use girdle::Dictionary;
+use girdle::dictionary::Result;
+use girdle::dictionary::SetType;
+use girdle::dictionary::display_width;
+
+use crate::cli::Args;
+use crate::load_word_list;
+
+const INCLUDE: usize = 0;
+const EXCLUDE: usize = 1;
+const POSITIONS: usize = 2;
+const LABELS: [&str; 3] = ["Include:", "Exclude:", "Positions:"];
+
+// The title, the input fields and the results heading come before the
+// results pane; the message and the key help follow it.
+const FIELDS_ROW: usize = 2;
+const RESULTS_ROW: usize = 7;
+const FOOTER_ROWS: usize = 2;
+const LABEL_WIDTH: usize = 11;
+const COLUMN_GAP: usize = 2;
+
+const HELP: &str = "\
+Tab: next field  Up/Down/PgUp/PgDn: scroll  Ctrl-U: clear field  \
+Ctrl-R: reset  Esc: quit";
+
+struct Tui {
+    dictionary: Dictionary,
+    fields: [String; 3],
+    focus: usize,
+    offset: usize,
+    page: usize,
+    message: String
+}
+
+// Puts the terminal back however `run` returns.
+struct Screen;
+
+pub fn run(args: &Args) -> Result<()> {
+    let word_list = load_word_list(args.dict.as_deref())?;
+    let dictionary = Dictionary::from_word_list(&word_list, args.length);
+    args.apply(&dictionary);
+
+    let mut tui = Tui::new(dictionary);
+    tui.message = format!("Loaded {} words from {}.", word_list.len(),
+                          word_list.path().display());
+
+    let _screen = Screen::enter()?;
+    loop {
+        tui.draw()?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Release && !tui.key(key) {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Screen {
+    fn enter() -> io::Result<Screen> {
+        terminal::enable_raw_mode()?;
+        if let Err(error) = execute!(io::stdout(), EnterAlternateScreen) {
+            let _ = terminal::disable_raw_mode();
+            return Err(error);
+        }
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+impl Tui {
+    fn new(dictionary: Dictionary) -> Tui {
+        let positions = (1..=dictionary.length())
+            .map(|pos| dictionary.char_position(pos).unwrap_or('.'))
+            .collect::<String>();
+        let fields = [
+            dictionary.included_chars().iter().collect(),
+            dictionary.excluded_chars().iter().collect(),
+            positions.trim_end_matches('.').to_string()
+        ];
+
+        Tui {
+            dictionary,
+            fields,
+            focus: INCLUDE,
+            offset: 0,
+            page: 1,
+            message: String::new()
+        }
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (width as usize, height as usize);
+        let length = self.dictionary.length();
+        let matches = self.dictionary.remaining();
+
+        let cell = matches.iter().map(|word| display_width(word)).max()
+            .unwrap_or(length) + COLUMN_GAP;
+        let columns = (width / cell).max(1);
+        let rows = matches.len().div_ceil(columns);
+        self.page = height.saturating_sub(RESULTS_ROW + FOOTER_ROWS).max(1);
+        self.offset = self.offset.min(rows.saturating_sub(self.page));
+
+        let mut out = io::stdout().lock();
+        queue!(out, Hide, Clear(ClearType::All), MoveTo(0, 0),
+               SetAttribute(Attribute::Bold),
+               Print(format!("Girdle: {}-letter words", length)),
+               SetAttribute(Attribute::Reset))?;
+
+        for (i, label) in LABELS.iter().enumerate() {
+            queue!(out, MoveTo(0, (FIELDS_ROW + i) as u16))?;
+            if i == self.focus {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(out, Print(format!("{:<1$}", label, LABEL_WIDTH - 1)),
+                   SetAttribute(Attribute::Reset), Print(" "),
+                   Print(&self.fields[i]))?;
+        }
+
+        let heading = match matches.is_empty() {
+            true => String::from("Results (no matches)"),
+            false if rows <= self.page => {
+                format!("Results ({} matches)", matches.len())
+            },
+            false => {
+                let last = (self.offset + self.page).min(rows);
+                format!("Results ({} matches, rows {}-{} of {})",
+                        matches.len(), self.offset + 1, last, rows)
+            }
+        };
+        queue!(out, MoveTo(0, (RESULTS_ROW - 1) as u16),
+               SetAttribute(Attribute::Bold),
+               Print(truncate(&heading, width)),
+               SetAttribute(Attribute::Reset))?;
+
+        let shown = rows.saturating_sub(self.offset).min(self.page);
+        for row in 0..shown {
+            let start = (self.offset + row) * columns;
+            let line = matches[start..].iter().take(columns)
+                .map(|word| pad(word, cell))
+                .collect::<String>();
+            queue!(out, MoveTo(0, (RESULTS_ROW + row) as u16),
+                   Print(line.trim_end()))?;
+        }
+
+        let footer = height.saturating_sub(FOOTER_ROWS);
+        queue!(out, MoveTo(0, footer as u16),
+               Print(truncate(&self.message, width)),
+               MoveTo(0, (footer + 1) as u16),
+               Print(truncate(HELP, width)))?;
+
+        let column = LABEL_WIDTH + display_width(&self.fields[self.focus]);
+        queue!(out, MoveTo(column as u16, (FIELDS_ROW + self.focus) as u16),
+               Show)?;
+        out.flush()
+    }
+
+    fn key(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('c') | KeyCode::Char('q') => return false,
+                KeyCode::Char('r') => self.reset(),
+                KeyCode::Char('u') => {
+                    self.fields[self.focus].clear();
+                    self.apply();
+                },
+                _ => ()
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Tab | KeyCode::Enter => {
+                self.focus = (self.focus + 1) % LABELS.len();
+            },
+            KeyCode::BackTab => {
+                self.focus = (self.focus + LABELS.len() - 1) % LABELS.len();
+            },
+
+            KeyCode::Up => self.offset = self.offset.saturating_sub(1),
+            KeyCode::Down => self.offset = self.offset.saturating_add(1),
+            KeyCode::PageUp => {
+                self.offset = self.offset.saturating_sub(self.page);
+            },
+            KeyCode::PageDown => {
+                self.offset = self.offset.saturating_add(self.page);
+            },
+            KeyCode::Home => self.offset = 0,
+            KeyCode::End => self.offset = usize::MAX,
+
+            KeyCode::Backspace => {
+                let removed = self.fields[self.focus].pop();
+                if removed.is_some() {
+                    self.apply();
+                }
+            },
+            KeyCode::Char(ch) => self.insert(ch),
+            _ => ()
+        }
+        true
+    }
+
+    fn insert(&mut self, ch: char) {
+        let ch = ch.to_lowercase().next().unwrap_or(ch);
+        let valid = match self.focus {
+            POSITIONS => ch == '.' || self.dictionary.is_letter(ch),
+            _ => self.dictionary.is_letter(ch)
+        };
+        if !valid {
+            self.message = format!("'{}' is not a valid character.", ch);
+            return;
+        }
+
+        let length = self.dictionary.length();
+        if self.focus == POSITIONS &&
+            self.fields[POSITIONS].chars().count() >= length
+        {
+            self.message = format!("Position pattern must be {} characters.",
+                                   length);
+            return;
+        }
+
+        self.fields[self.focus].push(ch);
+        self.apply();
+    }
+
+    fn apply(&mut self) {
+        let text = &self.fields[self.focus];
+        match self.focus {
+            INCLUDE => self.set_chars(SetType::Included, text),
+            EXCLUDE => self.set_chars(SetType::Excluded, text),
+            _ => self.set_positions(text)
+        }
+        self.offset = 0;
+        self.message.clear();
+    }
+
+    fn set_chars(&self, set_type: SetType, text: &str) {
+        self.dictionary.clear_set(set_type);
+        for ch in text.chars() {
+            self.dictionary.add_char(set_type, ch);
+        }
+    }
+
+    fn set_positions(&self, text: &str) {
+        let pattern = text.chars().collect::<Vec<_>>();
+        for pos in 1..=self.dictionary.length() {
+            match pattern.get(pos-1) {
+                Some(ch) if *ch != '.' => {
+                    self.dictionary.set_char_position(pos, *ch);
+                },
+                _ => self.dictionary.unset_char_position(pos)
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.dictionary.reset();
+        for field in &mut self.fields {
+            field.clear();
+        }
+        self.offset = 0;
+        self.message = String::from("Reset all constraints.");
+    }
+}
+
+fn pad(word: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(word));
+    format!("{}{}", word, " ".repeat(padding))
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    s.chars().take(width).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tui(words: &[&str]) -> Tui {
+        let words = words.iter().map(|word| word.to_string())
+            .collect::<Vec<_>>();
+        Tui::new(Dictionary::from_words(&words, 5))
+    }
+
+    fn type_keys(tui: &mut Tui, keys: &str) {
+        for ch in keys.chars() {
+            let code = match ch {
+                '\t' => KeyCode::Tab,
+                '\x08' => KeyCode::Backspace,
+                ch => KeyCode::Char(ch)
+            };
+            assert!(tui.key(KeyEvent::new(code, KeyModifiers::NONE)));
+        }
+    }
+
+    #[test]
+    fn fields_filter_the_results() {
+        let mut tui = tui(&["crane", "slate", "trace", "zebra"]);
+        type_keys(&mut tui, "Ae");
+        assert_eq!(tui.fields[INCLUDE], "ae");
+        assert_eq!(tui.dictionary.remaining().len(), 4);
+
+        type_keys(&mut tui, "\tz\t.R");
+        assert_eq!(tui.fields[EXCLUDE], "z");
+        assert_eq!(tui.fields[POSITIONS], ".r");
+        assert_eq!(tui.dictionary.remaining().to_vec(), ["crane", "trace"]);
+
+        type_keys(&mut tui, "\x08\x08");
+        assert_eq!(tui.dictionary.remaining().to_vec(),
+                   ["crane", "slate", "trace"]);
+    }
+
+    #[test]
+    fn invalid_keys_are_refused() {
+        let mut tui = tui(&["crane", "slate"]);
+        type_keys(&mut tui, "1\t\t");
+        assert_eq!(tui.message, "'1' is not a valid character.");
+        assert_eq!(tui.fields[INCLUDE], "");
+
+        type_keys(&mut tui, "crane.");
+        assert_eq!(tui.fields[POSITIONS], "crane");
+        assert_eq!(tui.message, "Position pattern must be 5 characters.");
+    }
+
+    #[test]
+    fn fields_start_from_the_arguments() {
+        let words = ["crane", "slate"].iter().map(|word| word.to_string())
+            .collect::<Vec<_>>();
+        let dictionary = Dictionary::from_words(&words, 5);
+        dictionary.add_char(SetType::Included, 'a');
+        dictionary.set_char_position(2, 'r');
+
+        let mut tui = Tui::new(dictionary);
+        assert_eq!(tui.fields, ["a", "", ".r"]);
+        let reset = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert!(tui.key(reset));
+        assert_eq!(tui.fields, ["", "", ""]);
+        assert_eq!(tui.dictionary.remaining().len(), 2);
+    }
+}